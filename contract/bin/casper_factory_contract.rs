@@ -0,0 +1,436 @@
+#![no_std]
+#![no_main]
+#![feature(default_alloc_error_handler)]
+
+// We need to explicitly import the std alloc crate and `alloc::string::String` as we're in a
+// `no_std` environment.
+extern crate alloc;
+
+use core::convert::TryFrom;
+
+use alloc::{
+    boxed::Box,
+    collections::BTreeSet,
+    format,
+    string::{String, ToString},
+    vec,
+};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_ido_contract::{
+    enums::AuctionPhase,
+    structs::{AuctionRecord, Time},
+    Error, Factory,
+};
+
+use casper_types::{
+    account::AccountHash, runtime_args, system::CallStackElement, ApiError, CLType, CLTyped,
+    CLValue, ContractHash, ContractPackageHash, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Group, Key, Parameter, RuntimeArgs, URef, U256,
+};
+use contract_utils::{AdminControl, ContractContext, OnChainContractStorage};
+
+#[derive(Default)]
+struct CasperFactoryContract(OnChainContractStorage);
+
+impl ContractContext<OnChainContractStorage> for CasperFactoryContract {
+    fn storage(&self) -> &OnChainContractStorage {
+        &self.0
+    }
+}
+
+impl Factory<OnChainContractStorage> for CasperFactoryContract {}
+impl AdminControl<OnChainContractStorage> for CasperFactoryContract {}
+
+impl CasperFactoryContract {
+    fn constructor(&mut self, fee_denominator: U256, fee_wallet: AccountHash) {
+        Factory::init(self, fee_denominator, fee_wallet);
+        AdminControl::init(self);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn constructor() {
+    let fee_denominator: U256 = runtime::get_named_arg("fee_denominator");
+    let fee_wallet: AccountHash = {
+        let fee_wallet_str: String = runtime::get_named_arg("fee_wallet");
+        AccountHash::from_formatted_str(&fee_wallet_str).unwrap()
+    };
+    CasperFactoryContract::default().constructor(fee_denominator, fee_wallet);
+    let default_admin = runtime::get_caller();
+    CasperFactoryContract::default().add_admin_without_checked(Key::from(default_admin));
+}
+
+/// Contract which called the current entry point
+fn calling_contract() -> ContractHash {
+    match runtime::get_call_stack().iter().rev().nth(1) {
+        Some(CallStackElement::StoredContract { contract_hash, .. }) => *contract_hash,
+        _ => runtime::revert(ApiError::PermissionDenied),
+    }
+}
+
+/// Called by the constructor of a new auction, which registers itself in the same deploy.
+/// The auction hash comes from the call stack, and the account of the deploy must be factory admin.
+#[no_mangle]
+pub extern "C" fn create_auction() {
+    let auction_start_time: Time = runtime::get_named_arg("auction_start_time");
+    let auction_end_time: Time = runtime::get_named_arg("auction_end_time");
+    let auction_contract = calling_contract();
+    let creator = match runtime::get_call_stack().first() {
+        Some(CallStackElement::Session { account_hash }) => *account_hash,
+        _ => runtime::revert(ApiError::PermissionDenied),
+    };
+    if !CasperFactoryContract::default().is_admin(Key::from(creator)) {
+        runtime::revert(ApiError::PermissionDenied);
+    }
+    CasperFactoryContract::default().add_auction(
+        auction_contract,
+        auction_start_time,
+        auction_end_time,
+        creator,
+    );
+}
+
+/// Called by an auction when its times change, so the registry phase stays current
+#[no_mangle]
+pub extern "C" fn update_auction_times() {
+    let auction_start_time: Time = runtime::get_named_arg("auction_start_time");
+    let auction_end_time: Time = runtime::get_named_arg("auction_end_time");
+    CasperFactoryContract::default().update_auction_times(
+        calling_contract(),
+        auction_start_time,
+        auction_end_time,
+    );
+}
+
+/// Register an auction installed before the factory, only admin call
+#[no_mangle]
+pub extern "C" fn add_auction() {
+    let auction_contract: ContractHash = {
+        let auction_contract_str: String = runtime::get_named_arg("auction_contract");
+        ContractHash::from_formatted_str(&auction_contract_str).unwrap()
+    };
+    let auction_start_time: Time = runtime::get_named_arg("auction_start_time");
+    let auction_end_time: Time = runtime::get_named_arg("auction_end_time");
+    let creator = runtime::get_caller();
+    if !CasperFactoryContract::default().is_admin(Key::from(creator)) {
+        runtime::revert(ApiError::PermissionDenied);
+    }
+    CasperFactoryContract::default().add_auction(
+        auction_contract,
+        auction_start_time,
+        auction_end_time,
+        creator,
+    );
+}
+
+#[no_mangle]
+pub extern "C" fn remove_auction() {
+    let auction_contract: ContractHash = {
+        let auction_contract_str: String = runtime::get_named_arg("auction_contract");
+        ContractHash::from_formatted_str(&auction_contract_str).unwrap()
+    };
+    CasperFactoryContract::default().assert_caller_is_admin();
+    CasperFactoryContract::default().remove_auction(auction_contract);
+}
+
+#[no_mangle]
+pub extern "C" fn set_auction_info() {
+    let auction_contract: ContractHash = {
+        let auction_contract_str: String = runtime::get_named_arg("auction_contract");
+        ContractHash::from_formatted_str(&auction_contract_str).unwrap()
+    };
+    let info: String = runtime::get_named_arg("info");
+    CasperFactoryContract::default().assert_caller_is_admin();
+    CasperFactoryContract::default().set_auction_info(auction_contract, info);
+}
+
+#[no_mangle]
+pub extern "C" fn get_auction() {
+    let auction_contract: ContractHash = {
+        let auction_contract_str: String = runtime::get_named_arg("auction_contract");
+        ContractHash::from_formatted_str(&auction_contract_str).unwrap()
+    };
+    let auction = CasperFactoryContract::default().get_auction(auction_contract);
+    runtime::ret(CLValue::from_t(auction).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn get_auctions_length() {
+    let length = CasperFactoryContract::default().get_auctions_length();
+    runtime::ret(CLValue::from_t(length).unwrap_or_revert());
+}
+
+/// `phase` is `0` upcoming, `1` live or `2` ended, `None` for every auction.
+/// `offset` is the registry index the page starts at, the registry ends at `next_auction_index`.
+#[no_mangle]
+pub extern "C" fn get_auctions() {
+    let phase: Option<AuctionPhase> = {
+        let phase: Option<u8> = runtime::get_named_arg("phase");
+        phase.map(|phase| AuctionPhase::try_from(phase).unwrap_or_revert_with(Error::InvalidStatus))
+    };
+    let offset: u32 = runtime::get_named_arg("offset");
+    let limit: u32 = runtime::get_named_arg("limit");
+    let auctions = CasperFactoryContract::default().get_auctions(phase, offset, limit);
+    runtime::ret(CLValue::from_t(auctions).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn set_fee_denominator() {
+    let fee_denominator: U256 = runtime::get_named_arg("fee_denominator");
+    CasperFactoryContract::default().assert_caller_is_admin();
+    CasperFactoryContract::default().set_fee_denominator(fee_denominator);
+}
+
+#[no_mangle]
+pub extern "C" fn get_fee_denominator() {
+    let fee_denominator = CasperFactoryContract::default().get_fee_denominator();
+    runtime::ret(CLValue::from_t(fee_denominator).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn set_fee_wallet() {
+    let fee_wallet: AccountHash = {
+        let fee_wallet_str: String = runtime::get_named_arg("fee_wallet");
+        AccountHash::from_formatted_str(&fee_wallet_str).unwrap()
+    };
+    CasperFactoryContract::default().assert_caller_is_admin();
+    CasperFactoryContract::default().set_fee_wallet(fee_wallet);
+}
+
+#[no_mangle]
+pub extern "C" fn get_fee_wallet() {
+    let fee_wallet = CasperFactoryContract::default().get_fee_wallet();
+    runtime::ret(CLValue::from_t(fee_wallet).unwrap_or_revert());
+}
+
+/// Revert with `ApiError::PermissionDenied` if `caller` is not admin of the factory
+#[no_mangle]
+pub extern "C" fn assert_caller_is_admin() {
+    let caller: AccountHash = runtime::get_named_arg("caller");
+    if !CasperFactoryContract::default().is_admin(Key::from(caller)) {
+        runtime::revert(ApiError::PermissionDenied);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn add_admin() {
+    let admin: AccountHash = {
+        let admin_string: String = runtime::get_named_arg("admin");
+        AccountHash::from_formatted_str(&admin_string).unwrap()
+    };
+    CasperFactoryContract::default().add_admin(Key::from(admin));
+}
+
+#[no_mangle]
+pub extern "C" fn remove_admin() {
+    let admin: AccountHash = {
+        let admin_string: String = runtime::get_named_arg("admin");
+        AccountHash::from_formatted_str(&admin_string).unwrap()
+    };
+    CasperFactoryContract::default().disable_admin(Key::from(admin));
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let contract_name: String = runtime::get_named_arg("contract_name");
+    let fee_denominator: U256 = runtime::get_named_arg("fee_denominator");
+    let fee_wallet: String = runtime::get_named_arg("fee_wallet");
+
+    let (contract_hash, _) = storage::new_contract(
+        get_entry_points(),
+        None,
+        Some(format!("{}_contract_package_hash", contract_name)),
+        Some(format!("{}_contract_access_token", contract_name)),
+    );
+
+    let package_hash: ContractPackageHash = ContractPackageHash::new(
+        runtime::get_key(&format!("{}_contract_package_hash", contract_name))
+            .unwrap_or_revert()
+            .into_hash()
+            .unwrap_or_revert(),
+    );
+    let constructor_access: URef =
+        storage::create_contract_user_group(package_hash, "constructor", 1, Default::default())
+            .unwrap_or_revert()
+            .pop()
+            .unwrap_or_revert();
+
+    let constructor_args = runtime_args! {
+        "fee_denominator" => fee_denominator,
+        "fee_wallet" => fee_wallet
+    };
+    let _: () = runtime::call_contract(contract_hash, "constructor", constructor_args);
+
+    let mut urefs = BTreeSet::new();
+    urefs.insert(constructor_access);
+    storage::remove_contract_user_group_urefs(package_hash, "constructor", urefs)
+        .unwrap_or_revert();
+
+    runtime::put_key(
+        &format!("{}_contract_hash", contract_name),
+        contract_hash.into(),
+    );
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![
+            Parameter::new("fee_denominator".to_string(), CLType::U256),
+            Parameter::new("fee_wallet".to_string(), CLType::String),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "create_auction",
+        vec![
+            Parameter::new("auction_start_time".to_string(), CLType::U64),
+            Parameter::new("auction_end_time".to_string(), CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "update_auction_times",
+        vec![
+            Parameter::new("auction_start_time".to_string(), CLType::U64),
+            Parameter::new("auction_end_time".to_string(), CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "add_auction",
+        vec![
+            Parameter::new("auction_contract".to_string(), CLType::String),
+            Parameter::new("auction_start_time".to_string(), CLType::U64),
+            Parameter::new("auction_end_time".to_string(), CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "remove_auction",
+        vec![Parameter::new(
+            "auction_contract".to_string(),
+            CLType::String,
+        )],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_auction_info",
+        vec![
+            Parameter::new("auction_contract".to_string(), CLType::String),
+            Parameter::new("info".to_string(), CLType::String),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_auction",
+        vec![Parameter::new(
+            "auction_contract".to_string(),
+            CLType::String,
+        )],
+        AuctionRecord::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_auctions_length",
+        vec![],
+        CLType::U32,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_auctions",
+        vec![
+            Parameter::new("phase".to_string(), CLType::Option(Box::new(CLType::U8))),
+            Parameter::new("offset".to_string(), CLType::U32),
+            Parameter::new("limit".to_string(), CLType::U32),
+        ],
+        CLType::List(Box::new(AuctionRecord::cl_type())),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_fee_denominator",
+        vec![Parameter::new("fee_denominator".to_string(), CLType::U256)],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_fee_denominator",
+        vec![],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_fee_wallet",
+        vec![Parameter::new("fee_wallet".to_string(), CLType::String)],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_fee_wallet",
+        vec![],
+        CLType::ByteArray(32),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "assert_caller_is_admin",
+        vec![Parameter::new("caller".to_string(), CLType::ByteArray(32))],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "add_admin",
+        vec![Parameter::new("admin".to_string(), CLType::String)],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "remove_admin",
+        vec![Parameter::new("admin".to_string(), CLType::String)],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points
+}
@@ -6,6 +6,8 @@
 // `no_std` environment.
 extern crate alloc;
 
+use core::convert::TryFrom;
+
 use alloc::{
     boxed::Box,
     collections::{BTreeMap, BTreeSet},
@@ -16,17 +18,22 @@ use alloc::{
 };
 use casper_contract::{
     contract_api::{runtime, storage},
+    ext_ffi,
     unwrap_or_revert::UnwrapOrRevert,
 };
 use casper_ido_contract::{
-    structs::{Schedules, Time},
-    CasperIdo,
+    constants::PRICE_DECIMALS,
+    enums::MerkleFormat,
+    event::CasperIdoEvent,
+    libs::merkle_tree::MerkleProof,
+    structs::{AuctionInfo, MerkleRootRecord, Round, Schedules, Time, Vesting},
+    CasperIdo, Error,
 };
 
 use casper_types::{
-    account::AccountHash, contracts::NamedKeys, runtime_args, CLType, ContractHash,
-    ContractPackageHash, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Group, Key,
-    Parameter, RuntimeArgs, URef, U256,
+    account::AccountHash, api_error, bytesrepr, contracts::NamedKeys, runtime_args, CLType,
+    CLTyped, CLValue, ContractHash, ContractPackageHash, EntryPoint, EntryPointAccess,
+    EntryPointType, EntryPoints, Group, Key, Parameter, RuntimeArgs, URef, U256,
 };
 use contract_utils::{AdminControl, ContractContext, OnChainContractStorage, ReentrancyGuard};
 
@@ -50,9 +57,11 @@ impl CasperIdoContract {
         auction_end_time: Time,
         auction_token_price: U256,
         auction_token_capacity: U256,
+        soft_cap: U256,
         pay_token: Option<ContractHash>,
         schedules: Schedules,
         treasury_wallet: AccountHash,
+        factory: ContractHash,
     ) {
         CasperIdo::init(
             self,
@@ -60,9 +69,11 @@ impl CasperIdoContract {
             auction_end_time,
             auction_token_price,
             auction_token_capacity,
+            soft_cap,
             pay_token,
             schedules,
             treasury_wallet,
+            factory,
         );
         AdminControl::init(self);
         ReentrancyGuard::init(self);
@@ -75,6 +86,7 @@ pub extern "C" fn constructor() {
     let auction_end_time: Time = runtime::get_named_arg("auction_end_time");
     let auction_token_price: U256 = runtime::get_named_arg("auction_token_price");
     let auction_token_capacity: U256 = runtime::get_named_arg("auction_token_capacity");
+    let soft_cap: U256 = runtime::get_named_arg("soft_cap");
     let pay_token: Option<ContractHash> = {
         let pay_token_str: Option<String> = runtime::get_named_arg("pay_token");
         pay_token_str.map(|str| ContractHash::from_formatted_str(&str).unwrap())
@@ -84,27 +96,79 @@ pub extern "C" fn constructor() {
         let treasury_wallet_str: String = runtime::get_named_arg("treasury_wallet");
         AccountHash::from_formatted_str(&treasury_wallet_str).unwrap()
     };
+    let factory: ContractHash = {
+        let factory_str: String = runtime::get_named_arg("factory_contract");
+        ContractHash::from_formatted_str(&factory_str).unwrap()
+    };
     CasperIdoContract::default().constructor(
         auction_start_time,
         auction_end_time,
         auction_token_price,
         auction_token_capacity,
+        soft_cap,
         pay_token,
         schedules,
         treasury_wallet,
+        factory,
     );
     let default_admin = runtime::get_caller();
     CasperIdoContract::default().add_admin_without_checked(Key::from(default_admin))
 }
 
+/// Serialized value of the named argument `name`
+fn get_named_arg_bytes(name: &str) -> Vec<u8> {
+    let mut arg_size: usize = 0;
+    let ret = unsafe {
+        ext_ffi::casper_get_named_arg_size(
+            name.as_bytes().as_ptr(),
+            name.len(),
+            &mut arg_size as *mut usize,
+        )
+    };
+    api_error::result_from(ret).unwrap_or_revert();
+    let mut arg_bytes = vec![0u8; arg_size];
+    if arg_size > 0 {
+        let ret = unsafe {
+            ext_ffi::casper_get_named_arg(
+                name.as_bytes().as_ptr(),
+                name.len(),
+                arg_bytes.as_mut_ptr(),
+                arg_size,
+            )
+        };
+        api_error::result_from(ret).unwrap_or_revert();
+    }
+    arg_bytes
+}
+
+/// Read the `proof` argument in the format of the current whitelist, rounds use the sorted format.
+/// A proof in the shape of the other format reverts with `MalformedProof`.
+fn get_proof_arg(round_id: Option<&String>) -> MerkleProof {
+    let proof_bytes = get_named_arg_bytes("proof");
+    let merkle_format = match round_id {
+        Some(_) => MerkleFormat::Sorted,
+        None => CasperIdoContract::default().merkle_format(),
+    };
+    match merkle_format {
+        MerkleFormat::Sorted => bytesrepr::deserialize(proof_bytes).map(MerkleProof::Sorted),
+        MerkleFormat::Legacy => bytesrepr::deserialize(proof_bytes).map(MerkleProof::Legacy),
+    }
+    .unwrap_or_revert_with(Error::MalformedProof)
+}
+
 #[no_mangle]
 pub extern "C" fn create_order() {
     let caller = runtime::get_caller();
     let tier: U256 = runtime::get_named_arg("tier");
-    let proof: Vec<(String, u8)> = runtime::get_named_arg("proof");
+    let round_id: Option<String> = runtime::get_named_arg("round_id");
+    let proof = get_proof_arg(round_id.as_ref());
     let amount: U256 = runtime::get_named_arg("amount");
+    let pay_token: Option<ContractHash> = {
+        let pay_token_str: Option<String> = runtime::get_named_arg("pay_token");
+        pay_token_str.map(|str| ContractHash::from_formatted_str(&str).unwrap())
+    };
     CasperIdoContract::default().set_reentrancy();
-    CasperIdoContract::default().create_order(caller, tier, proof, amount);
+    CasperIdoContract::default().create_order(caller, tier, proof, amount, pay_token, round_id);
     CasperIdoContract::default().clear_reentrancy();
 }
 
@@ -112,10 +176,19 @@ pub extern "C" fn create_order() {
 pub extern "C" fn create_order_cspr() {
     let caller = runtime::get_caller();
     let tier: U256 = runtime::get_named_arg("tier");
-    let proof: Vec<(String, u8)> = runtime::get_named_arg("proof");
+    let round_id: Option<String> = runtime::get_named_arg("round_id");
+    let proof = get_proof_arg(round_id.as_ref());
     let deposit_purse: URef = runtime::get_named_arg("deposit_purse");
     CasperIdoContract::default().set_reentrancy();
-    CasperIdoContract::default().create_order_cspr(caller, tier, proof, deposit_purse);
+    CasperIdoContract::default().create_order_cspr(caller, tier, proof, deposit_purse, round_id);
+    CasperIdoContract::default().clear_reentrancy();
+}
+
+#[no_mangle]
+pub extern "C" fn cancel_order() {
+    let caller = runtime::get_caller();
+    CasperIdoContract::default().set_reentrancy();
+    CasperIdoContract::default().cancel_order(caller);
     CasperIdoContract::default().clear_reentrancy();
 }
 
@@ -126,6 +199,42 @@ pub extern "C" fn add_orders() {
     CasperIdoContract::default().add_orders(orders);
 }
 
+#[no_mangle]
+pub extern "C" fn add_verified_orders() {
+    let orders: Vec<(AccountHash, U256, U256)> = {
+        let orders: Vec<(String, U256, U256)> = runtime::get_named_arg("orders");
+        orders
+            .into_iter()
+            .map(|(account, tier, amount)| {
+                (
+                    AccountHash::from_formatted_str(&account).unwrap(),
+                    tier,
+                    amount,
+                )
+            })
+            .collect()
+    };
+    let proof: Vec<[u8; 32]> = runtime::get_named_arg("proof");
+    let proof_flags: Vec<bool> = runtime::get_named_arg("proof_flags");
+    CasperIdoContract::default().assert_caller_is_admin();
+    CasperIdoContract::default().add_verified_orders(orders, proof, proof_flags);
+}
+
+#[no_mangle]
+pub extern "C" fn register_whitelist() {
+    let accounts: Vec<(AccountHash, U256)> = {
+        let accounts: Vec<(String, U256)> = runtime::get_named_arg("accounts");
+        accounts
+            .into_iter()
+            .map(|(account, tier)| (AccountHash::from_formatted_str(&account).unwrap(), tier))
+            .collect()
+    };
+    let proof: Vec<[u8; 32]> = runtime::get_named_arg("proof");
+    let proof_flags: Vec<bool> = runtime::get_named_arg("proof_flags");
+    CasperIdoContract::default().assert_caller_is_admin();
+    CasperIdoContract::default().register_whitelist(accounts, proof, proof_flags);
+}
+
 #[no_mangle]
 pub extern "C" fn claim() {
     let caller = runtime::get_caller();
@@ -136,6 +245,186 @@ pub extern "C" fn claim() {
     CasperIdoContract::default().clear_reentrancy();
 }
 
+#[no_mangle]
+pub extern "C" fn claim_all() {
+    let caller = runtime::get_caller();
+
+    CasperIdoContract::default().set_reentrancy();
+    CasperIdoContract::default().claim_all(caller);
+    CasperIdoContract::default().clear_reentrancy();
+}
+
+#[no_mangle]
+pub extern "C" fn finalize() {
+    CasperIdoContract::default().assert_caller_is_admin();
+    CasperIdoContract::default().finalize();
+}
+
+#[no_mangle]
+pub extern "C" fn cancel_auction() {
+    CasperIdoContract::default().assert_caller_is_admin();
+    CasperIdoContract::default().set_reentrancy();
+    CasperIdoContract::default().cancel_auction();
+    CasperIdoContract::default().clear_reentrancy();
+}
+
+#[no_mangle]
+pub extern "C" fn status() {
+    let status = CasperIdoContract::default().status();
+    runtime::ret(CLValue::from_t(status).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn get_order() {
+    let account: AccountHash = {
+        let account_str: String = runtime::get_named_arg("account");
+        AccountHash::from_formatted_str(&account_str).unwrap()
+    };
+    let order = CasperIdoContract::default().order(account);
+    runtime::ret(CLValue::from_t(order).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn get_claimed() {
+    let account: AccountHash = {
+        let account_str: String = runtime::get_named_arg("account");
+        AccountHash::from_formatted_str(&account_str).unwrap()
+    };
+    let schedule_time: Time = runtime::get_named_arg("schedule_time");
+    let claimed = CasperIdoContract::default().is_claimed(account, schedule_time);
+    runtime::ret(CLValue::from_t(claimed).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn claimable() {
+    let account: AccountHash = {
+        let account_str: String = runtime::get_named_arg("account");
+        AccountHash::from_formatted_str(&account_str).unwrap()
+    };
+    let claimable = CasperIdoContract::default().claimable_amount(account);
+    runtime::ret(CLValue::from_t(claimable).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn get_round() {
+    let round_id: String = runtime::get_named_arg("round_id");
+    let round = CasperIdoContract::default().round(&round_id);
+    runtime::ret(CLValue::from_t(round).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn round_order() {
+    let account: AccountHash = {
+        let account_str: String = runtime::get_named_arg("account");
+        AccountHash::from_formatted_str(&account_str).unwrap()
+    };
+    let round_id: String = runtime::get_named_arg("round_id");
+    let round_order = CasperIdoContract::default().round_order(account, &round_id);
+    runtime::ret(CLValue::from_t(round_order).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn entitlement() {
+    let account: AccountHash = {
+        let account_str: String = runtime::get_named_arg("account");
+        AccountHash::from_formatted_str(&account_str).unwrap()
+    };
+    let entitlement = CasperIdoContract::default().entitlement(account);
+    runtime::ret(CLValue::from_t(entitlement).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn claimed_amount() {
+    let account: AccountHash = {
+        let account_str: String = runtime::get_named_arg("account");
+        AccountHash::from_formatted_str(&account_str).unwrap()
+    };
+    let claimed_amount = CasperIdoContract::default().claimed_amount(account);
+    runtime::ret(CLValue::from_t(claimed_amount).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn auction_info() {
+    let auction_info = CasperIdoContract::default().auction_info();
+    runtime::ret(CLValue::from_t(auction_info).unwrap_or_revert());
+}
+
+/// `pay_token` is `None` for CSPR
+#[no_mangle]
+pub extern "C" fn get_raised_amount() {
+    let pay_token: Option<ContractHash> = {
+        let pay_token_str: Option<String> = runtime::get_named_arg("pay_token");
+        pay_token_str.map(|str| ContractHash::from_formatted_str(&str).unwrap())
+    };
+    let raised_amount = CasperIdoContract::default().raised_amount(pay_token);
+    runtime::ret(CLValue::from_t(raised_amount).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn sold_amount() {
+    let sold_amount = CasperIdoContract::default().sold_amount();
+    runtime::ret(CLValue::from_t(sold_amount).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn withdraw_funds() {
+    CasperIdoContract::default().assert_caller_is_admin();
+    CasperIdoContract::default().set_reentrancy();
+    CasperIdoContract::default().withdraw_funds();
+    CasperIdoContract::default().clear_reentrancy();
+}
+
+#[no_mangle]
+pub extern "C" fn withdraw_unsold() {
+    CasperIdoContract::default().assert_caller_is_admin();
+    CasperIdoContract::default().set_reentrancy();
+    CasperIdoContract::default().withdraw_unsold();
+    CasperIdoContract::default().clear_reentrancy();
+}
+
+#[no_mangle]
+pub extern "C" fn set_claim_deadline() {
+    let claim_deadline: Time = runtime::get_named_arg("claim_deadline");
+    let sweep_wallet: Option<AccountHash> = {
+        let sweep_wallet_str: Option<String> = runtime::get_named_arg("sweep_wallet");
+        sweep_wallet_str.map(|str| AccountHash::from_formatted_str(&str).unwrap())
+    };
+    CasperIdoContract::default().assert_caller_is_admin();
+    CasperIdoContract::default().set_claim_deadline(claim_deadline, sweep_wallet);
+}
+
+#[no_mangle]
+pub extern "C" fn sweep_unclaimed() {
+    CasperIdoContract::default().assert_caller_is_admin();
+    CasperIdoContract::default().set_reentrancy();
+    CasperIdoContract::default().sweep_unclaimed();
+    CasperIdoContract::default().clear_reentrancy();
+}
+
+#[no_mangle]
+pub extern "C" fn claim_refund() {
+    let caller = runtime::get_caller();
+    CasperIdoContract::default().set_reentrancy();
+    CasperIdoContract::default().claim_refund(caller);
+    CasperIdoContract::default().clear_reentrancy();
+}
+
+#[no_mangle]
+pub extern "C" fn refund() {
+    let caller = runtime::get_caller();
+    CasperIdoContract::default().set_reentrancy();
+    CasperIdoContract::default().refund(caller);
+    CasperIdoContract::default().clear_reentrancy();
+}
+
+#[no_mangle]
+pub extern "C" fn withdraw_auction_token() {
+    let caller = runtime::get_caller();
+    CasperIdoContract::default().set_reentrancy();
+    CasperIdoContract::default().withdraw_auction_token(caller);
+    CasperIdoContract::default().clear_reentrancy();
+}
+
 #[no_mangle]
 pub extern "C" fn set_auction_token() {
     let auction_token: ContractHash = {
@@ -147,11 +436,25 @@ pub extern "C" fn set_auction_token() {
     CasperIdoContract::default().set_auction_token(auction_token, auction_token_capacity);
 }
 
+/// `pay_token` is `None` for CSPR
+#[no_mangle]
+pub extern "C" fn add_pay_token() {
+    let pay_token: Option<ContractHash> = {
+        let pay_token_str: Option<String> = runtime::get_named_arg("pay_token");
+        pay_token_str.map(|str| ContractHash::from_formatted_str(&str).unwrap())
+    };
+    let price: U256 = runtime::get_named_arg("price");
+    let price_denominator: Option<U256> = runtime::get_named_arg("price_denominator");
+    CasperIdoContract::default().assert_caller_is_admin();
+    CasperIdoContract::default().add_pay_token(pay_token, price, price_denominator);
+}
+
 #[no_mangle]
 pub extern "C" fn change_auction_token_price() {
     let auction_token_price: U256 = runtime::get_named_arg("price");
+    let price_denominator: Option<U256> = runtime::get_named_arg("price_denominator");
     CasperIdoContract::default().assert_caller_is_admin();
-    CasperIdoContract::default().change_auction_token_price(auction_token_price);
+    CasperIdoContract::default().change_auction_token_price(auction_token_price, price_denominator);
 }
 
 #[no_mangle]
@@ -177,11 +480,102 @@ pub extern "C" fn change_time_schedules() {
     );
 }
 
+#[no_mangle]
+pub extern "C" fn set_partial_fill() {
+    let partial_fill: bool = runtime::get_named_arg("partial_fill");
+    CasperIdoContract::default().assert_caller_is_admin();
+    CasperIdoContract::default().set_partial_fill(partial_fill);
+}
+
+#[no_mangle]
+pub extern "C" fn set_oversubscription() {
+    let oversubscription: bool = runtime::get_named_arg("oversubscription");
+    CasperIdoContract::default().assert_caller_is_admin();
+    CasperIdoContract::default().set_oversubscription(oversubscription);
+}
+
+#[no_mangle]
+pub extern "C" fn set_round() {
+    let round_id: String = runtime::get_named_arg("round_id");
+    let round = Round {
+        merkle_root: runtime::get_named_arg("merkle_root"),
+        start_time: runtime::get_named_arg("start_time"),
+        end_time: runtime::get_named_arg("end_time"),
+        max_order: runtime::get_named_arg("max_order"),
+        price: {
+            let price: Option<U256> = runtime::get_named_arg("price");
+            let price_denominator: Option<U256> = runtime::get_named_arg("price_denominator");
+            price.map(|price| {
+                (
+                    price,
+                    price_denominator.unwrap_or_else(|| U256::exp10(PRICE_DECIMALS.into())),
+                )
+            })
+        },
+    };
+    CasperIdoContract::default().assert_caller_is_admin();
+    CasperIdoContract::default().set_round(round_id, round);
+}
+
+#[no_mangle]
+pub extern "C" fn set_vesting() {
+    let vesting = Vesting {
+        tge_time: runtime::get_named_arg("tge_time"),
+        tge_percent: runtime::get_named_arg("tge_percent"),
+        cliff_duration: runtime::get_named_arg("cliff_duration"),
+        linear_duration: runtime::get_named_arg("linear_duration"),
+    };
+    CasperIdoContract::default().assert_caller_is_admin();
+    CasperIdoContract::default().set_vesting(vesting);
+}
+
+#[no_mangle]
+pub extern "C" fn set_fee_numerator() {
+    let caller = runtime::get_caller();
+    let fee_numerator: U256 = runtime::get_named_arg("fee_numerator");
+    CasperIdoContract::default().set_fee_numerator(caller, fee_numerator);
+}
+
 #[no_mangle]
 pub extern "C" fn set_merkle_root() {
-    let merkle_root: String = runtime::get_named_arg("merkle_root");
+    let merkle_root: [u8; 32] = runtime::get_named_arg("merkle_root");
+    let merkle_format: MerkleFormat = {
+        let merkle_format_u8: Option<u8> = runtime::get_named_arg("merkle_format");
+        merkle_format_u8
+            .map(|value| {
+                MerkleFormat::try_from(value).unwrap_or_revert_with(Error::InvalidMerkleRoot)
+            })
+            .unwrap_or(MerkleFormat::Sorted)
+    };
+    // Proofs against the replaced root stay valid this long, `None` replaces it at once
+    let grace_period: Option<Time> = runtime::get_named_arg("grace_period");
     CasperIdoContract::default().assert_caller_is_admin();
-    CasperIdoContract::default().set_merkle_root(merkle_root);
+    CasperIdoContract::default().set_merkle_root(
+        merkle_root,
+        merkle_format,
+        grace_period.unwrap_or_default(),
+    );
+}
+
+#[no_mangle]
+pub extern "C" fn revoke_leaves() {
+    let leaves: Vec<[u8; 32]> = runtime::get_named_arg("leaves");
+    let revoked: bool = runtime::get_named_arg("revoked");
+    CasperIdoContract::default().assert_caller_is_admin();
+    CasperIdoContract::default().revoke_leaves(leaves, revoked);
+}
+
+#[no_mangle]
+pub extern "C" fn get_merkle_root_history() {
+    let history = CasperIdoContract::default().merkle_root_history();
+    runtime::ret(CLValue::from_t(history).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn is_leaf_revoked() {
+    let leaf: [u8; 32] = runtime::get_named_arg("leaf");
+    let revoked = CasperIdoContract::default().is_leaf_revoked(&leaf);
+    runtime::ret(CLValue::from_t(revoked).unwrap_or_revert());
 }
 
 #[no_mangle]
@@ -191,6 +585,9 @@ pub extern "C" fn add_admin() {
         AccountHash::from_formatted_str(&admin_string).unwrap()
     };
     CasperIdoContract::default().add_admin(Key::from(admin));
+    CasperIdoContract::default().emit(CasperIdoEvent::AdminAdded {
+        admin: Key::from(admin),
+    });
 }
 
 #[no_mangle]
@@ -200,6 +597,9 @@ pub extern "C" fn remove_admin() {
         AccountHash::from_formatted_str(&admin_string).unwrap()
     };
     CasperIdoContract::default().disable_admin(Key::from(admin));
+    CasperIdoContract::default().emit(CasperIdoEvent::AdminRemoved {
+        admin: Key::from(admin),
+    });
 }
 
 #[no_mangle]
@@ -209,9 +609,12 @@ pub extern "C" fn call() {
     let auction_end_time: Time = runtime::get_named_arg("auction_end_time");
     let auction_token_price: U256 = runtime::get_named_arg("auction_token_price");
     let auction_token_capacity: U256 = runtime::get_named_arg("auction_token_capacity");
+    let soft_cap: U256 = runtime::get_named_arg("soft_cap");
     let pay_token: Option<String> = runtime::get_named_arg("pay_token");
     let schedules: Schedules = runtime::get_named_arg("schedules");
     let treasury_wallet: String = runtime::get_named_arg("treasury_wallet");
+    // The new auction registers itself to this factory, which takes the platform fee
+    let factory_contract: String = runtime::get_named_arg("factory_contract");
     let exist_contract_package_hash: Option<ContractPackageHash> = {
         let contract_package_hash_str: Option<String> =
             runtime::get_named_arg("contract_package_hash");
@@ -256,9 +659,11 @@ pub extern "C" fn call() {
         "auction_end_time" => auction_end_time,
         "auction_token_price" => auction_token_price,
         "auction_token_capacity" => auction_token_capacity,
+        "soft_cap" => soft_cap,
         "pay_token" => pay_token,
         "schedules" => schedules,
-        "treasury_wallet" => treasury_wallet
+        "treasury_wallet" => treasury_wallet,
+        "factory_contract" => factory_contract
     };
     let _: () = runtime::call_contract(contract_hash, "constructor", constructor_args);
 
@@ -283,6 +688,7 @@ fn get_entry_points() -> EntryPoints {
             Parameter::new("auction_end_time".to_string(), CLType::U64),
             Parameter::new("auction_token_price".to_string(), CLType::U256),
             Parameter::new("auction_token_capacity".to_string(), CLType::U256),
+            Parameter::new("soft_cap".to_string(), CLType::U256),
             Parameter::new("pay_token".to_string(), CLType::String),
             Parameter::new(
                 "schedules".to_string(),
@@ -292,6 +698,7 @@ fn get_entry_points() -> EntryPoints {
                 },
             ),
             Parameter::new("treasury_wallet".to_string(), CLType::U64),
+            Parameter::new("factory_contract".to_string(), CLType::String),
         ],
         CLType::Unit,
         EntryPointAccess::Groups(vec![Group::new("constructor")]),
@@ -302,14 +709,20 @@ fn get_entry_points() -> EntryPoints {
         "create_order",
         vec![
             Parameter::new("tier".to_string(), CLType::U256),
+            // `Vec<(String, u8)>` while the whitelist uses the legacy format
             Parameter::new(
                 "proof".to_string(),
-                CLType::List(Box::new(CLType::Tuple2([
-                    Box::new(CLType::String),
-                    Box::new(CLType::U8),
-                ]))),
+                CLType::List(Box::new(CLType::ByteArray(32))),
             ),
             Parameter::new("amount".to_string(), CLType::U256),
+            Parameter::new(
+                "pay_token".to_string(),
+                CLType::Option(Box::new(CLType::String)),
+            ),
+            Parameter::new(
+                "round_id".to_string(),
+                CLType::Option(Box::new(CLType::String)),
+            ),
         ],
         CLType::Unit,
         EntryPointAccess::Public,
@@ -320,20 +733,30 @@ fn get_entry_points() -> EntryPoints {
         "create_order_cspr",
         vec![
             Parameter::new("tier".to_string(), CLType::U256),
+            // `Vec<(String, u8)>` while the whitelist uses the legacy format
             Parameter::new(
                 "proof".to_string(),
-                CLType::List(Box::new(CLType::Tuple2([
-                    Box::new(CLType::String),
-                    Box::new(CLType::U8),
-                ]))),
+                CLType::List(Box::new(CLType::ByteArray(32))),
             ),
             Parameter::new("deposit_purse".to_string(), CLType::URef),
+            Parameter::new(
+                "round_id".to_string(),
+                CLType::Option(Box::new(CLType::String)),
+            ),
         ],
         CLType::Unit,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        "cancel_order",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         "add_orders",
         vec![Parameter::new(
@@ -348,6 +771,57 @@ fn get_entry_points() -> EntryPoints {
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        "add_verified_orders",
+        vec![
+            // `(account, tier, amount)` in the leaf order of the multiproof
+            Parameter::new(
+                "orders".to_string(),
+                CLType::List(Box::new(CLType::Tuple3([
+                    Box::new(CLType::String),
+                    Box::new(CLType::U256),
+                    Box::new(CLType::U256),
+                ]))),
+            ),
+            Parameter::new(
+                "proof".to_string(),
+                CLType::List(Box::new(CLType::ByteArray(32))),
+            ),
+            Parameter::new(
+                "proof_flags".to_string(),
+                CLType::List(Box::new(CLType::Bool)),
+            ),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "register_whitelist",
+        vec![
+            // `(account, tier)` in the leaf order of the multiproof
+            Parameter::new(
+                "accounts".to_string(),
+                CLType::List(Box::new(CLType::Tuple2([
+                    Box::new(CLType::String),
+                    Box::new(CLType::U256),
+                ]))),
+            ),
+            Parameter::new(
+                "proof".to_string(),
+                CLType::List(Box::new(CLType::ByteArray(32))),
+            ),
+            Parameter::new(
+                "proof_flags".to_string(),
+                CLType::List(Box::new(CLType::Bool)),
+            ),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         "claim",
         vec![Parameter::new("schedule_time".to_string(), CLType::U64)],
@@ -356,6 +830,189 @@ fn get_entry_points() -> EntryPoints {
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        "claim_all",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "finalize",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "cancel_auction",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "status",
+        vec![],
+        CLType::U8,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_order",
+        vec![Parameter::new("account".to_string(), CLType::String)],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_claimed",
+        vec![
+            Parameter::new("account".to_string(), CLType::String),
+            Parameter::new("schedule_time".to_string(), CLType::U64),
+        ],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "claimable",
+        vec![Parameter::new("account".to_string(), CLType::String)],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_round",
+        vec![Parameter::new("round_id".to_string(), CLType::String)],
+        CLType::Option(Box::new(Round::cl_type())),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "round_order",
+        vec![
+            Parameter::new("account".to_string(), CLType::String),
+            Parameter::new("round_id".to_string(), CLType::String),
+        ],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "entitlement",
+        vec![Parameter::new("account".to_string(), CLType::String)],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "claimed_amount",
+        vec![Parameter::new("account".to_string(), CLType::String)],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "auction_info",
+        vec![],
+        AuctionInfo::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_raised_amount",
+        vec![Parameter::new(
+            "pay_token".to_string(),
+            CLType::Option(Box::new(CLType::String)),
+        )],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "sold_amount",
+        vec![],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "withdraw_funds",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "withdraw_unsold",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_claim_deadline",
+        vec![
+            Parameter::new("claim_deadline".to_string(), CLType::U64),
+            Parameter::new(
+                "sweep_wallet".to_string(),
+                CLType::Option(Box::new(CLType::String)),
+            ),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "sweep_unclaimed",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "claim_refund",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "refund",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "withdraw_auction_token",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         "set_auction_token",
         vec![
@@ -367,9 +1024,33 @@ fn get_entry_points() -> EntryPoints {
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        "add_pay_token",
+        vec![
+            Parameter::new(
+                "pay_token".to_string(),
+                CLType::Option(Box::new(CLType::String)),
+            ),
+            Parameter::new("price".to_string(), CLType::U256),
+            Parameter::new(
+                "price_denominator".to_string(),
+                CLType::Option(Box::new(CLType::U256)),
+            ),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         "change_auction_token_price",
-        vec![Parameter::new("price".to_string(), CLType::U256)],
+        vec![
+            Parameter::new("price".to_string(), CLType::U256),
+            Parameter::new(
+                "price_denominator".to_string(),
+                CLType::Option(Box::new(CLType::U256)),
+            ),
+        ],
         CLType::Unit,
         EntryPointAccess::Public,
         EntryPointType::Contract,
@@ -404,14 +1085,113 @@ fn get_entry_points() -> EntryPoints {
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_partial_fill",
+        vec![Parameter::new("partial_fill".to_string(), CLType::Bool)],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_oversubscription",
+        vec![Parameter::new("oversubscription".to_string(), CLType::Bool)],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_round",
+        vec![
+            Parameter::new("round_id".to_string(), CLType::String),
+            Parameter::new("merkle_root".to_string(), CLType::ByteArray(32)),
+            Parameter::new("start_time".to_string(), CLType::U64),
+            Parameter::new("end_time".to_string(), CLType::U64),
+            Parameter::new(
+                "max_order".to_string(),
+                CLType::Option(Box::new(CLType::U256)),
+            ),
+            Parameter::new("price".to_string(), CLType::Option(Box::new(CLType::U256))),
+            Parameter::new(
+                "price_denominator".to_string(),
+                CLType::Option(Box::new(CLType::U256)),
+            ),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_vesting",
+        vec![
+            Parameter::new("tge_time".to_string(), CLType::U64),
+            Parameter::new("tge_percent".to_string(), CLType::U256),
+            Parameter::new("cliff_duration".to_string(), CLType::U64),
+            Parameter::new("linear_duration".to_string(), CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_fee_numerator",
+        vec![Parameter::new("fee_numerator".to_string(), CLType::U256)],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         "set_merkle_root",
-        vec![Parameter::new("merkle_root".to_string(), CLType::String)],
+        vec![
+            Parameter::new("merkle_root".to_string(), CLType::ByteArray(32)),
+            Parameter::new(
+                "merkle_format".to_string(),
+                CLType::Option(Box::new(CLType::U8)),
+            ),
+            Parameter::new(
+                "grace_period".to_string(),
+                CLType::Option(Box::new(CLType::U64)),
+            ),
+        ],
         CLType::Unit,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        "revoke_leaves",
+        vec![
+            Parameter::new(
+                "leaves".to_string(),
+                CLType::List(Box::new(CLType::ByteArray(32))),
+            ),
+            Parameter::new("revoked".to_string(), CLType::Bool),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_merkle_root_history",
+        vec![],
+        CLType::List(Box::new(MerkleRootRecord::cl_type())),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "is_leaf_revoked",
+        vec![Parameter::new("leaf".to_string(), CLType::ByteArray(32))],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         "add_admin",
         vec![Parameter::new("admin".to_string(), CLType::String)],
@@ -0,0 +1,150 @@
+#![no_std]
+#![no_main]
+#![feature(default_alloc_error_handler)]
+
+//! Session code calling a getter of the ido or factory contract and storing the value under
+//! the caller's `result` named key, so off-chain clients can read it.
+
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+use casper_contract::contract_api::{runtime, storage};
+use casper_ido_contract::{
+    structs::{AuctionInfo, AuctionRecord, MerkleRootRecord},
+    Error,
+};
+use casper_types::{
+    bytesrepr::{FromBytes, ToBytes},
+    runtime_args, CLTyped, ContractHash, RuntimeArgs, U256,
+};
+
+const RESULT_KEY: &str = "result";
+
+fn store_result<T: CLTyped + ToBytes>(value: T) {
+    runtime::put_key(RESULT_KEY, storage::new_uref(value).into());
+}
+
+fn call_getter<T: CLTyped + FromBytes>(
+    contract_hash: ContractHash,
+    entry_point: &str,
+    args: RuntimeArgs,
+) -> T {
+    runtime::call_contract::<T>(contract_hash, entry_point, args)
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let contract_hash: ContractHash = {
+        let contract_hash_string: String = runtime::get_named_arg("contract_hash");
+        ContractHash::from_formatted_str(&contract_hash_string).unwrap()
+    };
+    let entry_point: String = runtime::get_named_arg("entry_point");
+
+    match entry_point.as_str() {
+        "get_order" => {
+            let account: String = runtime::get_named_arg("account");
+            store_result(call_getter::<U256>(
+                contract_hash,
+                "get_order",
+                runtime_args! { "account" => account },
+            ));
+        }
+        "get_claimed" => {
+            let account: String = runtime::get_named_arg("account");
+            let schedule_time: u64 = runtime::get_named_arg("schedule_time");
+            store_result(call_getter::<bool>(
+                contract_hash,
+                "get_claimed",
+                runtime_args! { "account" => account, "schedule_time" => schedule_time },
+            ));
+        }
+        "claimable" => {
+            let account: String = runtime::get_named_arg("account");
+            store_result(call_getter::<U256>(
+                contract_hash,
+                "claimable",
+                runtime_args! { "account" => account },
+            ));
+        }
+        "round_order" => {
+            let account: String = runtime::get_named_arg("account");
+            let round_id: String = runtime::get_named_arg("round_id");
+            store_result(call_getter::<U256>(
+                contract_hash,
+                "round_order",
+                runtime_args! { "account" => account, "round_id" => round_id },
+            ));
+        }
+        "entitlement" | "claimed_amount" => {
+            let account: String = runtime::get_named_arg("account");
+            store_result(call_getter::<U256>(
+                contract_hash,
+                &entry_point,
+                runtime_args! { "account" => account },
+            ));
+        }
+        "is_leaf_revoked" => {
+            let leaf: [u8; 32] = runtime::get_named_arg("leaf");
+            store_result(call_getter::<bool>(
+                contract_hash,
+                "is_leaf_revoked",
+                runtime_args! { "leaf" => leaf },
+            ));
+        }
+        "get_merkle_root_history" => {
+            store_result(call_getter::<Vec<MerkleRootRecord>>(
+                contract_hash,
+                "get_merkle_root_history",
+                runtime_args! {},
+            ));
+        }
+        "auction_info" => {
+            store_result(call_getter::<AuctionInfo>(
+                contract_hash,
+                "auction_info",
+                runtime_args! {},
+            ));
+        }
+        "get_raised_amount" => {
+            let pay_token: Option<String> = runtime::get_named_arg("pay_token");
+            store_result(call_getter::<U256>(
+                contract_hash,
+                "get_raised_amount",
+                runtime_args! { "pay_token" => pay_token },
+            ));
+        }
+        "sold_amount" => {
+            store_result(call_getter::<U256>(
+                contract_hash,
+                "sold_amount",
+                runtime_args! {},
+            ));
+        }
+        "get_auction" => {
+            let auction_contract: String = runtime::get_named_arg("auction_contract");
+            store_result(call_getter::<AuctionRecord>(
+                contract_hash,
+                "get_auction",
+                runtime_args! { "auction_contract" => auction_contract },
+            ));
+        }
+        "get_auctions_length" => {
+            store_result(call_getter::<u32>(
+                contract_hash,
+                "get_auctions_length",
+                runtime_args! {},
+            ));
+        }
+        "get_auctions" => {
+            let phase: Option<u8> = runtime::get_named_arg("phase");
+            let offset: u32 = runtime::get_named_arg("offset");
+            let limit: u32 = runtime::get_named_arg("limit");
+            store_result(call_getter::<Vec<AuctionRecord>>(
+                contract_hash,
+                "get_auctions",
+                runtime_args! { "phase" => phase, "offset" => offset, "limit" => limit },
+            ));
+        }
+        _ => runtime::revert(Error::InvalidGetter),
+    }
+}
@@ -19,20 +19,35 @@ pub extern "C" fn call() {
         ContractHash::from_formatted_str(&contract_hash_string).unwrap()
     };
     let tier: U256 = runtime::get_named_arg("tier");
-    let proof: Vec<(String, u8)> = runtime::get_named_arg("proof");
     let amount: U512 = runtime::get_named_arg("amount");
+    // `proof` is forwarded as is, `merkle_format` tells its type. `None` is the sorted format.
+    let merkle_format: Option<u8> = runtime::get_named_arg("merkle_format");
+    // Rounds always use the sorted format
+    let round_id: Option<String> = runtime::get_named_arg("round_id");
 
     let deposit_purse = system::create_purse();
     let account_purse = account::get_main_purse();
     system::transfer_from_purse_to_purse(account_purse, deposit_purse, amount, None)
         .unwrap_or_revert();
-    let _ = runtime::call_contract::<()>(
-        contract_hash,
-        "create_order_cspr",
-        runtime_args! {
-          "tier" => tier,
-          "proof" => proof,
-          "deposit_purse" => deposit_purse,
-        },
-    );
+    let args = match merkle_format {
+        Some(0) => {
+            let proof: Vec<(String, u8)> = runtime::get_named_arg("proof");
+            runtime_args! {
+              "tier" => tier,
+              "proof" => proof,
+              "deposit_purse" => deposit_purse,
+              "round_id" => round_id,
+            }
+        }
+        _ => {
+            let proof: Vec<[u8; 32]> = runtime::get_named_arg("proof");
+            runtime_args! {
+              "tier" => tier,
+              "proof" => proof,
+              "deposit_purse" => deposit_purse,
+              "round_id" => round_id,
+            }
+        }
+    };
+    let _ = runtime::call_contract::<()>(contract_hash, "create_order_cspr", args);
 }
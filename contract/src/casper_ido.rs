@@ -1,9 +1,4 @@
-use alloc::{
-    collections::BTreeMap,
-    format,
-    string::{String, ToString},
-    vec::Vec,
-};
+use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
 use casper_contract::{
     contract_api::{runtime, system},
     unwrap_or_revert::UnwrapOrRevert,
@@ -12,22 +7,49 @@ use casper_types::{account::AccountHash, ContractHash, ContractPackageHash, Key,
 use contract_utils::{ContractContext, ContractStorage};
 
 use crate::{
+    constants::{CSPR_DECIMALS, MAX_MERKLE_ROOT_HISTORY, PRICE_DECIMALS},
     data::{
-        set_creator, set_schedules, Claims, Orders, _get_merkle_root, _get_sold_amount,
-        _get_total_participants, _get_treasury_wallet, _set_merkle_root, _set_sold_amount,
-        _set_total_participants, _set_treasury_wallet, get_auction_end_time,
+        Claimed, Claims, Entitlements, Events, FeeAmounts, Orders, PayTokenPrices, Payments,
+        Raised, RaisedAtClose, RefundClaims, RefundedPayments, Registrations, RevokedLeaves,
+        RoundOrders, _get_contract_purse, _get_merkle_root, _get_sold_amount,
+        _get_total_participants, _get_treasury_wallet, _set_contract_purse, _set_merkle_root,
+        _set_sold_amount, _set_total_participants, _set_treasury_wallet, get_auction_end_time,
         get_auction_start_time, get_auction_token, get_auction_token_capacity,
-        get_auction_token_price, get_creator, get_pay_token, get_schedules, set_auction_end_time,
-        set_auction_start_time, set_auction_token, set_auction_token_capacity,
-        set_auction_token_price, set_pay_token,
+        get_auction_token_decimals, get_auction_token_price, get_auction_token_price_denominator,
+        get_auction_token_withdrawn, get_claim_deadline, get_claiming_started, get_creator,
+        get_factory, get_fee_denominator, get_fee_numerator, get_funds_withdrawn,
+        get_merkle_format, get_merkle_root_activation_time, get_merkle_root_history,
+        get_oversubscription, get_partial_fill, get_pay_token, get_pay_token_decimals,
+        get_pay_tokens, get_rounds, get_schedules, get_soft_cap, get_status, get_sweep_wallet,
+        get_total_claimed, get_unclaimed_swept, get_unsold_withdrawn, get_vesting,
+        set_auction_end_time, set_auction_start_time, set_auction_token,
+        set_auction_token_capacity, set_auction_token_decimals, set_auction_token_price,
+        set_auction_token_price_denominator, set_auction_token_withdrawn, set_claim_deadline,
+        set_claiming_started, set_creator, set_factory, set_fee_denominator, set_fee_numerator,
+        set_funds_withdrawn, set_merkle_format, set_merkle_root_activation_time,
+        set_merkle_root_history, set_oversubscription, set_partial_fill, set_pay_token,
+        set_pay_token_decimals, set_pay_tokens, set_rounds, set_schedules, set_soft_cap,
+        set_status, set_sweep_wallet, set_total_claimed, set_unclaimed_swept, set_unsold_withdrawn,
+        set_vesting,
     },
-    enums::Address,
+    enums::{Address, AuctionStatus, MerkleFormat},
     event::{self, CasperIdoEvent},
-    libs::{conversion::u512_to_u256, merkle_tree},
-    structs::{Schedules, Time},
-    Error, IERC20,
+    libs::{
+        conversion::{u256_to_512, u512_to_u256},
+        merkle_tree::{self, MerkleProof},
+    },
+    structs::{AuctionInfo, MerkleRootRecord, Round, Schedules, Time, Vesting},
+    Error, IFactory, IERC20,
 };
 
+/// Statuses before the results are locked by `finalize`
+const NOT_FINALIZED: &[AuctionStatus] = &[
+    AuctionStatus::Created,
+    AuctionStatus::TokenDeposited,
+    AuctionStatus::Live,
+    AuctionStatus::Ended,
+];
+
 pub trait CasperIdo<Storage: ContractStorage>: ContractContext<Storage> {
     fn init(
         &mut self,
@@ -35,25 +57,80 @@ pub trait CasperIdo<Storage: ContractStorage>: ContractContext<Storage> {
         auction_end_time: Time,
         auction_token_price: U256,
         auction_token_capacity: U256,
+        soft_cap: U256,
         pay_token: Option<ContractHash>,
         schedules: Schedules,
         treasury_wallet: AccountHash,
+        factory: ContractHash,
     ) {
+        self._assert_valid_auction_time(auction_start_time, auction_end_time);
+        let price_denominator = U256::exp10(PRICE_DECIMALS.into());
+        self._assert_valid_auction_token_price(auction_token_price, price_denominator);
+        self._assert_valid_schedules(&schedules, auction_end_time);
+
         set_creator(runtime::get_caller());
         set_auction_start_time(auction_start_time);
         set_auction_end_time(auction_end_time);
 
         set_auction_token(ContractHash::new([0u8; 32]));
+        set_status(AuctionStatus::Created);
         set_auction_token_price(auction_token_price);
+        set_auction_token_price_denominator(price_denominator);
         set_auction_token_capacity(auction_token_capacity);
+        set_soft_cap(soft_cap);
         set_pay_token(pay_token);
+        set_pay_token_decimals(self._pay_token_decimals(pay_token));
         set_schedules(schedules);
-        _set_merkle_root("".to_string());
+        _set_merkle_root([0u8; 32]);
+        set_merkle_format(MerkleFormat::Sorted);
+        set_merkle_root_activation_time(Time::from(runtime::get_blocktime()));
+        set_merkle_root_history(Vec::new());
         _set_total_participants(0);
         _set_sold_amount(U256::from(0));
         _set_treasury_wallet(treasury_wallet);
+        _set_contract_purse(system::create_purse());
+        set_partial_fill(false);
+        set_oversubscription(false);
+        set_funds_withdrawn(false);
+        set_auction_token_withdrawn(false);
+        set_unsold_withdrawn(false);
+        set_claim_deadline(None);
+        set_sweep_wallet(None);
+        set_unclaimed_swept(false);
+        set_vesting(None);
+        set_total_claimed(U256::zero());
+        set_claiming_started(false);
         Orders::init();
         Claims::init();
+        RefundClaims::init();
+        Claimed::init();
+        Entitlements::init();
+        Registrations::init();
+        RoundOrders::init();
+        RevokedLeaves::init();
+        set_rounds(BTreeMap::new());
+        Events::init();
+        FeeAmounts::init();
+        PayTokenPrices::init();
+        Payments::init();
+        Raised::init();
+        RaisedAtClose::init();
+        RefundedPayments::init();
+        set_pay_tokens(Vec::new());
+
+        // Register to the factory registry, the deployer must be an admin of the factory
+        set_factory(factory);
+        IFactory::new(factory).create_auction(auction_start_time, auction_end_time);
+
+        self.emit(CasperIdoEvent::AuctionCreated {
+            creator: get_creator(),
+            auction_created_time: Time::from(runtime::get_blocktime()),
+            auction_start_time,
+            auction_end_time,
+            auction_token_price,
+            auction_token_capacity,
+            schedules: get_schedules(),
+        });
     }
 
     fn contract_package_hash(&self) -> ContractPackageHash {
@@ -61,7 +138,49 @@ pub trait CasperIdo<Storage: ContractStorage>: ContractContext<Storage> {
         ContractPackageHash::from(hash_addr)
     }
 
+    fn factory(&self) -> ContractHash {
+        get_factory()
+    }
+
+    /// Platform fee over the factory `fee_denominator`, only factory admin call before finalized.
+    /// The denominator is stored with the numerator so a later factory change can't move the fee.
+    fn set_fee_numerator(&mut self, caller: AccountHash, fee_numerator: U256) {
+        self._assert_status(NOT_FINALIZED);
+        let factory = IFactory::new(self.factory());
+        factory.assert_caller_is_admin(caller);
+        let fee_denominator = factory.get_fee_denominator();
+        if fee_denominator.is_zero() || fee_numerator.gt(&fee_denominator) {
+            runtime::revert(Error::InvalidFee);
+        }
+        set_fee_numerator(fee_numerator);
+        set_fee_denominator(fee_denominator);
+    }
+
+    fn fee_numerator(&self) -> U256 {
+        get_fee_numerator()
+    }
+
+    /// Part of `amount` taken as platform fee, zero until a fee is set
+    fn platform_fee_amount(&self, amount: U256) -> U256 {
+        let fee_numerator = self.fee_numerator();
+        match get_fee_denominator() {
+            Some(fee_denominator) if !fee_numerator.is_zero() => amount
+                .checked_mul(fee_numerator)
+                .unwrap_or_revert()
+                .checked_div(fee_denominator)
+                .unwrap_or_revert()
+                .min(amount),
+            _ => U256::zero(),
+        }
+    }
+
+    /// Platform fee taken by `withdraw_funds` in `pay_token`
+    fn fee_amount(&self, pay_token: Option<ContractHash>) -> U256 {
+        FeeAmounts::instance().get(&pay_token).unwrap_or_default()
+    }
+
     fn set_auction_token(&mut self, auction_token: ContractHash, auction_token_capacity: U256) {
+        self._assert_status(&[AuctionStatus::Created]);
         self._assert_null_auction_token();
 
         set_auction_token_capacity(auction_token_capacity);
@@ -72,123 +191,134 @@ pub trait CasperIdo<Storage: ContractStorage>: ContractContext<Storage> {
             auction_token_capacity,
         );
         set_auction_token(auction_token);
+        set_auction_token_decimals(IERC20::new(auction_token).decimals());
+        set_status(AuctionStatus::TokenDeposited);
+        self.emit(CasperIdoEvent::StatusChanged {
+            status: AuctionStatus::TokenDeposited,
+        });
     }
 
     /// Create order, caller must be whitelisted and can create in sale time.
+    /// `pay_token` is one of the accepted tokens, `None` pays with the main `pay_token`.
+    /// `round_id` orders in that round, `None` orders with the auction whitelist.
     fn create_order(
         &mut self,
         caller: AccountHash,
         tier: U256,
-        proof: Vec<(String, u8)>,
+        proof: MerkleProof,
         amount: U256,
+        pay_token: Option<ContractHash>,
+        round_id: Option<String>,
     ) {
         // Check caller is whitelisted
-        let leaf = format!("{}_{:?}", caller, tier);
-        merkle_tree::verify(self.merkle_root(), leaf, proof);
-
-        // Check current time is between sale time
-        self._assert_auction_time();
-
-        let pay_token = self.pay_token();
-
-        // Check payment is right
-        let order_amount = match pay_token {
-            Some(token) => {
-                IERC20::new(token).transfer_from(
-                    Address::from(caller),
-                    Address::from(self.treasury_wallet()),
-                    amount,
-                );
-                amount
-            }
-            None => {
-                runtime::revert(Error::InvalidPayToken);
-            }
-        };
+        let round = self._assert_can_order(caller, tier, proof, round_id.as_ref());
 
-        // Check order amount is less than tier
-        let exist_order_amount = Orders::instance()
-            .get(&Key::from(caller))
-            .unwrap_or(U256::zero());
+        // Check sale is live
+        self._assert_status(&[AuctionStatus::Live]);
 
-        let unchecked_new_order_amount = order_amount.checked_add(exist_order_amount).unwrap();
+        let pay_token = pay_token
+            .or_else(|| self.pay_token())
+            .unwrap_or_revert_with(Error::InvalidPayToken);
 
-        if tier.lt(&unchecked_new_order_amount) {
-            runtime::revert(Error::OutOfTier);
-        }
+        // Only the filled part is pulled, so the excess stays with the caller
+        let (pay_amount, order_amount) =
+            self._fill_payment(Some(pay_token), amount, round.and_then(|round| round.price));
+        IERC20::new(pay_token).transfer_from(
+            Address::from(caller),
+            Address::from(self.contract_package_hash()),
+            pay_amount,
+        );
 
-        if exist_order_amount.eq(&U256::zero()) {
-            self.increase_sold_amount_and_participants(order_amount);
-        } else {
-            self._increase_sold_amount(order_amount);
-        }
-        Orders::instance().set(&Key::from(caller), unchecked_new_order_amount);
+        self._add_order(caller, tier, order_amount, round_id.zip(round));
+        self._add_payment(caller, Some(pay_token), pay_amount);
     }
 
     fn create_order_cspr(
         &mut self,
         caller: AccountHash,
         tier: U256,
-        proof: Vec<(String, u8)>,
+        proof: MerkleProof,
         deposit_purse: URef,
+        round_id: Option<String>,
     ) {
         // Check caller is whitelisted
-        let leaf = format!("{}_{:?}", caller, tier);
-        merkle_tree::verify(self.merkle_root(), leaf, proof);
+        let round = self._assert_can_order(caller, tier, proof, round_id.as_ref());
 
-        // Check current time is between auction time
-        self._assert_auction_time();
+        // Check sale is live
+        self._assert_status(&[AuctionStatus::Live]);
 
-        let pay_token = self.pay_token();
+        // Reverts if CSPR is not accepted
+        let purse_balance = system::get_purse_balance(deposit_purse).unwrap_or_revert();
+        let (pay_amount, order_amount) = self._fill_payment(
+            None,
+            u512_to_u256(&purse_balance).unwrap_or_revert(),
+            round.and_then(|round| round.price),
+        );
+        let pay_amount_u512 = u256_to_512(&pay_amount).unwrap_or_revert();
 
-        // Check payment is right
-        let order_amount = match pay_token {
-            Some(_) => {
-                runtime::revert(Error::InvalidPayToken);
-            }
-            None => {
-                let purse_balance = system::get_purse_balance(deposit_purse).unwrap_or_revert();
+        system::transfer_from_purse_to_purse(
+            deposit_purse,
+            self.contract_purse(),
+            pay_amount_u512,
+            None,
+        )
+        .unwrap_or_revert();
 
-                system::transfer_from_purse_to_account(
-                    deposit_purse,
-                    self.treasury_wallet(),
-                    purse_balance,
-                    None,
-                )
+        // Return the excess of a partially filled order
+        let excess = purse_balance
+            .checked_sub(pay_amount_u512)
+            .unwrap_or_revert();
+        if !excess.is_zero() {
+            system::transfer_from_purse_to_account(deposit_purse, caller, excess, None)
                 .unwrap_or_revert();
-                u512_to_u256(&purse_balance).unwrap_or_revert()
-            }
-        };
+        }
 
-        // Check order amount is less than tier
-        let exist_order_amount = Orders::instance()
-            .get(&Key::from(caller))
-            .unwrap_or(U256::zero());
+        self._add_order(caller, tier, order_amount, round_id.zip(round));
+        self._add_payment(caller, None, pay_amount);
+    }
 
-        let unchecked_new_order_amount = order_amount.checked_add(exist_order_amount).unwrap();
+    /// Cancel order and refund the payment, only in sale time.
+    fn cancel_order(&mut self, caller: AccountHash) {
+        // Check sale is live
+        self._assert_status(&[AuctionStatus::Live]);
 
-        if tier.lt(&unchecked_new_order_amount) {
-            runtime::revert(Error::OutOfTier);
-        }
-        if exist_order_amount.eq(&U256::zero()) {
-            self.increase_sold_amount_and_participants(order_amount);
-        } else {
-            self._increase_sold_amount(order_amount);
+        let order_amount = Orders::instance()
+            .get(&Key::from(caller))
+            .unwrap_or_revert_with(Error::NotExistOrder);
+
+        Orders::instance().remove(&Key::from(caller));
+        for round_id in get_rounds().keys() {
+            RoundOrders::instance().remove(&Key::from(caller), round_id);
         }
-        Orders::instance().set(&Key::from(caller), unchecked_new_order_amount);
+        self._decrease_sold_amount(order_amount);
+        self._decrease_total_participants();
+
+        self._refund_payments(caller, order_amount, order_amount);
+        self.emit(CasperIdoEvent::OrderCancelled {
+            account: caller,
+            amount: order_amount,
+        });
     }
 
-    /// Whitelisted user can claim after schedule time
+    /// Whitelisted user can claim after schedule time.
+    /// In vesting mode `schedule_time` is not used and everything vested so far is released.
     fn claim(&mut self, caller: AccountHash, schedule_time: Time) {
+        self._assert_status(&[AuctionStatus::Finalized]);
+        self._assert_before_claim_deadline();
+        set_claiming_started(true);
+
+        if let Some(vesting) = get_vesting() {
+            self._claim_vested(caller, vesting);
+            return;
+        }
+
         // Can claim after schedule
         let current_block_time = runtime::get_blocktime();
         if !schedule_time.lt(&u64::from(current_block_time)) {
             runtime::revert(Error::InvalidTime);
         }
 
-        let order_amount = Orders::instance()
-            .get(&Key::from(caller))
-            .unwrap_or_revert_with(Error::NotExistOrder);
+        let entitlement = self._fix_entitlement(caller);
 
         if Claims::instance()
             .get(&Key::from(caller), schedule_time)
@@ -197,80 +327,853 @@ pub trait CasperIdo<Storage: ContractStorage>: ContractContext<Storage> {
             runtime::revert(Error::AlreadyClaimed);
         }
 
-        let auction_token_instance = IERC20::new(self.auction_token());
-        let transfer_amount = {
-            let schedule_percent = *get_schedules()
-                .get(&schedule_time)
-                .unwrap_or_revert_with(Error::InvalidSchedule);
-            let auction_token_decimals = auction_token_instance.decimals();
-            let auction_token_price = get_auction_token_price();
-            order_amount
-                .checked_mul(schedule_percent)
-                .unwrap_or_revert()
-                .checked_div(U256::exp10(4))
-                .unwrap_or_revert()
-                .checked_mul(U256::exp10(auction_token_decimals.into()))
-                .unwrap_or_revert()
-                .checked_div(auction_token_price)
-                .unwrap_or_revert()
-        };
-        auction_token_instance.transfer(Address::from(caller), transfer_amount);
+        let schedule_percent = *get_schedules()
+            .get(&schedule_time)
+            .unwrap_or_revert_with(Error::InvalidSchedule);
+        let unlocked_percent = self
+            ._claimed_percent(caller)
+            .checked_add(schedule_percent)
+            .unwrap_or_revert();
         Claims::instance().set(&Key::from(caller), schedule_time, true);
+        let transfer_amount = self
+            ._unlocked_amount(entitlement, unlocked_percent)
+            .checked_sub(self.claimed_amount(caller))
+            .unwrap_or_revert();
+        self._transfer_claimed(caller, transfer_amount);
+    }
+
+    /// Claim every matured schedule which is not claimed yet with a single transfer
+    fn claim_all(&mut self, caller: AccountHash) {
+        self._assert_status(&[AuctionStatus::Finalized]);
+        self._assert_before_claim_deadline();
+        set_claiming_started(true);
+
+        if let Some(vesting) = get_vesting() {
+            self._claim_vested(caller, vesting);
+            return;
+        }
+
+        let entitlement = self._fix_entitlement(caller);
+
+        let (schedule_times, matured_percent) = self._claimable_schedules(caller);
+        if matured_percent.is_zero() {
+            runtime::revert(Error::NotExistClaimable);
+        }
+        let unlocked_percent = self
+            ._claimed_percent(caller)
+            .checked_add(matured_percent)
+            .unwrap_or_revert();
+        let claims = Claims::instance();
+        for schedule_time in schedule_times {
+            claims.set(&Key::from(caller), schedule_time, true);
+        }
+
+        let transfer_amount = self
+            ._unlocked_amount(entitlement, unlocked_percent)
+            .checked_sub(self.claimed_amount(caller))
+            .unwrap_or_revert();
+        self._transfer_claimed(caller, transfer_amount);
+    }
+
+    /// Matured schedules not claimed yet by `account` and the sum of their percents
+    fn _claimable_schedules(&self, account: AccountHash) -> (Vec<Time>, U256) {
+        let current_block_time = Time::from(runtime::get_blocktime());
+        let claims = Claims::instance();
+        let mut schedule_times = Vec::new();
+        let mut matured_percent = U256::zero();
+        for (schedule_time, schedule_percent) in get_schedules() {
+            if !schedule_time.lt(&current_block_time) {
+                break;
+            }
+            if claims.get(&Key::from(account), schedule_time).is_some() {
+                continue;
+            }
+            matured_percent = matured_percent
+                .checked_add(schedule_percent)
+                .unwrap_or_revert();
+            schedule_times.push(schedule_time);
+        }
+        (schedule_times, matured_percent)
+    }
+
+    /// Sum of the schedule percents already claimed by `account`
+    fn _claimed_percent(&self, account: AccountHash) -> U256 {
+        let claims = Claims::instance();
+        let mut claimed_percent = U256::zero();
+        for (schedule_time, schedule_percent) in get_schedules() {
+            if claims.get(&Key::from(account), schedule_time).is_some() {
+                claimed_percent = claimed_percent
+                    .checked_add(schedule_percent)
+                    .unwrap_or_revert();
+            }
+        }
+        claimed_percent
+    }
+
+    /// Part of `entitlement` unlocked by `percent` of the schedules,
+    /// rounded once on the cumulative percent so the last schedule pays out the exact remainder
+    fn _unlocked_amount(&self, entitlement: U256, percent: U256) -> U256 {
+        entitlement
+            .checked_mul(percent)
+            .unwrap_or_revert()
+            .checked_div(U256::exp10(4))
+            .unwrap_or_revert()
+    }
+
+    /// Total auction token amount `account` receives, fixed on its first claim
+    fn entitlement(&self, account: AccountHash) -> U256 {
+        Entitlements::instance()
+            .get(&Key::from(account))
+            .unwrap_or_else(|| self.auction_token_amount(self.filled_amount(self.order(account))))
+    }
+
+    /// Compute and store the entitlement of `account` unless it is already fixed
+    fn _fix_entitlement(&mut self, account: AccountHash) -> U256 {
+        let entitlements = Entitlements::instance();
+        if let Some(entitlement) = entitlements.get(&Key::from(account)) {
+            return entitlement;
+        }
+        let order_amount = Orders::instance()
+            .get(&Key::from(account))
+            .unwrap_or_revert_with(Error::NotExistOrder);
+        let entitlement = self.auction_token_amount(self.filled_amount(order_amount));
+        entitlements.set(&Key::from(account), entitlement);
+        entitlement
+    }
+
+    /// Release everything vested up to now minus what is already claimed
+    fn _claim_vested(&mut self, caller: AccountHash, vesting: Vesting) {
+        let entitlement = self._fix_entitlement(caller);
+
+        let current_block_time = Time::from(runtime::get_blocktime());
+        let transfer_amount = vesting
+            .vested_amount(entitlement, current_block_time)
+            .checked_sub(self.claimed_amount(caller))
+            .unwrap_or_revert();
+        if transfer_amount.is_zero() {
+            runtime::revert(Error::NotExistClaimable);
+        }
+        self._transfer_claimed(caller, transfer_amount);
+    }
+
+    /// Release escrowed payment to treasury wallet, only admin call after finalized
+    fn withdraw_funds(&mut self) {
+        self._assert_status(&[AuctionStatus::Finalized]);
+        if get_funds_withdrawn() {
+            runtime::revert(Error::AlreadyWithdrawn);
+        }
+        set_funds_withdrawn(true);
+
+        if self.sold_amount().is_zero() {
+            return;
+        }
+        for pay_token in self.pay_tokens() {
+            // Keep in the escrow what the accounts which did not claim their refund yet are owed
+            let unrefunded_payments = RaisedAtClose::instance()
+                .get(&pay_token)
+                .unwrap_or_default()
+                .checked_sub(
+                    RefundedPayments::instance()
+                        .get(&pay_token)
+                        .unwrap_or_default(),
+                )
+                .unwrap_or_revert();
+            let total_amount = self
+                .raised_amount(pay_token)
+                .checked_sub(self._unfilled_amount(unrefunded_payments))
+                .unwrap_or_revert();
+            if total_amount.is_zero() {
+                continue;
+            }
+
+            let fee_amount = self.platform_fee_amount(total_amount);
+            if !fee_amount.is_zero() {
+                let fee_wallet = IFactory::new(self.factory()).get_fee_wallet();
+                FeeAmounts::instance().set(&pay_token, fee_amount);
+                self._transfer_payment(pay_token, fee_wallet, fee_amount);
+                self.emit(CasperIdoEvent::FeeCollected {
+                    pay_token,
+                    fee_wallet,
+                    amount: fee_amount,
+                });
+            }
+
+            let amount = total_amount.checked_sub(fee_amount).unwrap_or_revert();
+            self._transfer_payment(pay_token, self.treasury_wallet(), amount);
+            self.emit(CasperIdoEvent::FundsWithdrawn {
+                pay_token,
+                treasury_wallet: self.treasury_wallet(),
+                amount,
+            });
+        }
+    }
+
+    /// Cancel the sale before finalized and return the deposited auction token, only admin call.
+    /// Participants reclaim their orders through `refund`.
+    fn cancel_auction(&mut self) {
+        self._assert_status(NOT_FINALIZED);
+        if self.auction_token().ne(&ContractHash::new([0u8; 32])) {
+            self._return_auction_token();
+        }
+        set_status(AuctionStatus::Cancelled);
+        self.emit(CasperIdoEvent::StatusChanged {
+            status: AuctionStatus::Cancelled,
+        });
+    }
+
+    /// Lock the results of an ended sale, only admin call
+    fn finalize(&mut self) {
+        let status = match self.status() {
+            AuctionStatus::Ended => AuctionStatus::Finalized,
+            AuctionStatus::Failed => AuctionStatus::Failed,
+            _ => runtime::revert(Error::InvalidStatus),
+        };
+        if status.eq(&AuctionStatus::Finalized) {
+            for pay_token in self.pay_tokens() {
+                RaisedAtClose::instance().set(&pay_token, self.raised_amount(pay_token));
+            }
+        }
+        set_status(status);
+        self.emit(CasperIdoEvent::StatusChanged { status });
+    }
+
+    /// Return the unfilled part of an order in an oversubscribed sale
+    fn claim_refund(&mut self, caller: AccountHash) {
+        self._assert_status(&[AuctionStatus::Finalized]);
+
+        let order_amount = Orders::instance()
+            .get(&Key::from(caller))
+            .unwrap_or_revert_with(Error::NotExistOrder);
+
+        if RefundClaims::instance().get(&Key::from(caller)).is_some() {
+            runtime::revert(Error::AlreadyClaimed);
+        }
+
+        let refund_amount = self._unfilled_amount(order_amount);
+        if refund_amount.is_zero() {
+            runtime::revert(Error::NotExistRefund);
+        }
+
+        RefundClaims::instance().set(&Key::from(caller), true);
+        let refunded_payments = RefundedPayments::instance();
+        for pay_token in self.pay_tokens() {
+            refunded_payments.set(
+                &pay_token,
+                refunded_payments
+                    .get(&pay_token)
+                    .unwrap_or_default()
+                    .checked_add(self.payment(caller, pay_token))
+                    .unwrap_or_revert(),
+            );
+        }
+        let sold_amount = self.sold_amount();
+        self._refund_payments(
+            caller,
+            sold_amount
+                .checked_sub(self.total_filled_amount())
+                .unwrap_or_revert(),
+            sold_amount,
+        );
+        self.emit(CasperIdoEvent::Refunded {
+            account: caller,
+            amount: refund_amount,
+        });
+    }
+
+    /// Return the whole order of a failed or cancelled sale
+    fn refund(&mut self, caller: AccountHash) {
+        self._assert_status(&[AuctionStatus::Failed, AuctionStatus::Cancelled]);
+
+        let order_amount = Orders::instance()
+            .get(&Key::from(caller))
+            .unwrap_or_revert_with(Error::NotExistOrder);
+        Orders::instance().remove(&Key::from(caller));
+
+        self._refund_payments(caller, order_amount, order_amount);
+        self.emit(CasperIdoEvent::Refunded {
+            account: caller,
+            amount: order_amount,
+        });
+    }
+
+    /// Creator pulls back the deposited auction token of a failed sale
+    fn withdraw_auction_token(&mut self, caller: AccountHash) {
+        if caller.ne(&self.creator()) {
+            runtime::revert(Error::PermissionDenied);
+        }
+        if !self.is_failed() {
+            runtime::revert(Error::SaleNotFailed);
+        }
+        self._return_auction_token();
+    }
+
+    /// Send the auction token no order filled back to creator, only admin call after finalized
+    fn withdraw_unsold(&mut self) {
+        self._assert_status(&[AuctionStatus::Finalized]);
+        if get_unsold_withdrawn() || get_unclaimed_swept() {
+            runtime::revert(Error::AlreadyWithdrawn);
+        }
+        let unsold_amount = self.unsold_amount();
+        if unsold_amount.is_zero() {
+            runtime::revert(Error::NothingToWithdraw);
+        }
+        set_unsold_withdrawn(true);
+
+        let creator = self.creator();
+        IERC20::new(self.auction_token()).transfer(Address::from(creator), unsold_amount);
+        self.emit(CasperIdoEvent::UnsoldWithdrawn {
+            recipient: creator,
+            amount: unsold_amount,
+        });
+    }
+
+    /// Deposited auction token which no order filled
+    fn unsold_amount(&self) -> U256 {
+        self.auction_token_capacity()
+            .saturating_sub(self.auction_token_amount(self.total_filled_amount()))
+    }
+
+    /// Close claims at `claim_deadline`, the unclaimed auction token can then be swept to
+    /// `sweep_wallet`, creator when `None`. Only before finalized.
+    fn set_claim_deadline(&mut self, claim_deadline: Time, sweep_wallet: Option<AccountHash>) {
+        self._assert_status(NOT_FINALIZED);
+        set_claim_deadline(Some(claim_deadline));
+        set_sweep_wallet(sweep_wallet);
+        self._assert_valid_claim_deadline();
+    }
+
+    fn claim_deadline(&self) -> Option<Time> {
+        get_claim_deadline()
+    }
+
+    /// Send every auction token left after the claim deadline to the sweep wallet,
+    /// only admin call
+    fn sweep_unclaimed(&mut self) {
+        self._assert_status(&[AuctionStatus::Finalized]);
+        let claim_deadline = self
+            .claim_deadline()
+            .unwrap_or_revert_with(Error::InvalidClaimDeadline);
+        if !claim_deadline.lt(&Time::from(runtime::get_blocktime())) {
+            runtime::revert(Error::InvalidTime);
+        }
+        if get_unclaimed_swept() {
+            runtime::revert(Error::AlreadyWithdrawn);
+        }
+        set_unclaimed_swept(true);
+
+        let mut amount = self
+            .auction_token_capacity()
+            .checked_sub(self.total_claimed_amount())
+            .unwrap_or_revert();
+        if get_unsold_withdrawn() {
+            amount = amount.checked_sub(self.unsold_amount()).unwrap_or_revert();
+        }
+        if amount.is_zero() {
+            runtime::revert(Error::NothingToWithdraw);
+        }
+
+        let recipient = get_sweep_wallet().unwrap_or_else(|| self.creator());
+        IERC20::new(self.auction_token()).transfer(Address::from(recipient), amount);
+        self.emit(CasperIdoEvent::UnclaimedSwept { recipient, amount });
+    }
+
+    /// Enable or disable oversubscription, only before the sale starts
+    fn set_oversubscription(&mut self, oversubscription: bool) {
+        self._assert_status(&[AuctionStatus::Created, AuctionStatus::TokenDeposited]);
+        set_oversubscription(oversubscription);
     }
 
-    /// Set merkle_root , only admin call
-    fn set_merkle_root(&mut self, merkle_root: String) {
+    /// Switch claim to vesting mode, only before finalized
+    fn set_vesting(&mut self, vesting: Vesting) {
+        self._assert_status(NOT_FINALIZED);
+        if vesting.tge_percent.gt(&U256::exp10(4)) {
+            runtime::revert(Error::InvalidSchedulePercent);
+        }
+        if vesting.tge_time.lt(&get_auction_end_time()) {
+            runtime::revert(Error::ScheduleBeforeAuctionEnd);
+        }
+        set_vesting(Some(vesting));
+        self._assert_valid_claim_deadline();
+    }
+
+    /// Set merkle_root and the format of its proofs, only admin call.
+    /// Proofs against the replaced root are accepted for `grace_period` unless the format changes.
+    fn set_merkle_root(
+        &mut self,
+        merkle_root: [u8; 32],
+        merkle_format: MerkleFormat,
+        grace_period: Time,
+    ) {
+        self._assert_status(NOT_FINALIZED);
+        let time = Time::from(runtime::get_blocktime());
+        // Proofs are read in the current format, so a format change drops the history
+        let same_format = merkle_format.eq(&self.merkle_format());
+        let mut history = if same_format {
+            self.merkle_root_history()
+        } else {
+            Vec::new()
+        };
+        let previous_root = self.merkle_root();
+        if grace_period > 0 && same_format && previous_root.ne(&[0u8; 32]) {
+            history.push(MerkleRootRecord {
+                merkle_root: previous_root,
+                activation_time: get_merkle_root_activation_time(),
+                expiry_time: time.checked_add(grace_period).unwrap_or_revert(),
+            });
+        }
+        // Drop the oldest roots beyond the limit
+        if history.len() > MAX_MERKLE_ROOT_HISTORY {
+            history.drain(..history.len() - MAX_MERKLE_ROOT_HISTORY);
+        }
+        set_merkle_root_history(history);
+
         _set_merkle_root(merkle_root);
+        set_merkle_format(merkle_format);
+        set_merkle_root_activation_time(time);
+        self.emit(CasperIdoEvent::MerkleRootChanged {
+            merkle_root,
+            merkle_format,
+            grace_period,
+        });
+    }
+
+    /// Superseded roots whose proofs are still accepted
+    fn merkle_root_history(&self) -> Vec<MerkleRootRecord> {
+        let time = Time::from(runtime::get_blocktime());
+        get_merkle_root_history()
+            .into_iter()
+            .filter(|record| record.is_live(time))
+            .collect()
+    }
+
+    /// Revoke or restore whitelist leaves, a revoked leaf is refused against every root.
+    /// Leaves are `merkle_tree::leaf(account, tier)` whatever the format. Only admin call.
+    fn revoke_leaves(&mut self, leaves: Vec<[u8; 32]>, revoked: bool) {
+        self._assert_status(NOT_FINALIZED);
+        let revoked_leaves = RevokedLeaves::instance();
+        for leaf in leaves.iter() {
+            revoked_leaves.set(leaf, revoked);
+        }
+        self.emit(CasperIdoEvent::LeavesRevoked { leaves, revoked });
+    }
+
+    fn is_leaf_revoked(&self, leaf: &[u8; 32]) -> bool {
+        RevokedLeaves::instance().get(leaf).unwrap_or_default()
+    }
+
+    /// Add or replace the round `round_id`, only admin call.
+    /// Orders of a round are also limited to the auction time.
+    fn set_round(&mut self, round_id: String, round: Round) {
+        self._assert_status(NOT_FINALIZED);
+        if !round.start_time.lt(&round.end_time) {
+            runtime::revert(Error::InvalidRoundTime);
+        }
+        if let Some((price, denominator)) = round.price {
+            self._assert_valid_auction_token_price(price, denominator);
+        }
+        let mut rounds = get_rounds();
+        rounds.insert(round_id.clone(), round);
+        set_rounds(rounds);
+        self.emit(CasperIdoEvent::RoundSet {
+            round_id,
+            merkle_root: round.merkle_root,
+            start_time: round.start_time,
+            end_time: round.end_time,
+        });
+    }
+
+    fn round(&self, round_id: &String) -> Option<Round> {
+        get_rounds().get(round_id).copied()
+    }
+
+    /// Amount ordered by `account` in the round `round_id`
+    fn round_order(&self, account: AccountHash, round_id: &String) -> U256 {
+        RoundOrders::instance()
+            .get(&Key::from(account), round_id)
+            .unwrap_or_default()
     }
 
     fn add_orders(&mut self, orders: BTreeMap<String, U256>) {
+        self._assert_status(&[
+            AuctionStatus::TokenDeposited,
+            AuctionStatus::Live,
+            AuctionStatus::Ended,
+        ]);
         orders.iter().enumerate().for_each(|order| {
             let user_order = order.1;
             let account = AccountHash::from_formatted_str(user_order.0).unwrap();
             let order_amount = *user_order.1;
-            let exist_order_amount = Orders::instance()
-                .get(&Key::from(account))
-                .unwrap_or(U256::zero());
-            let unchecked_new_order_amount = exist_order_amount.checked_add(order_amount).unwrap();
-
-            if exist_order_amount.eq(&U256::zero()) {
-                self.increase_sold_amount_and_participants(order_amount);
-            } else {
-                self._increase_sold_amount(order_amount);
+            self._import_order(account, order_amount);
+        });
+    }
+
+    /// Import orders of whitelisted accounts proven together by one multiproof against the
+    /// current root, `orders` are `(account, tier, amount)` in the leaf order of the multiproof.
+    /// Only admin call.
+    fn add_verified_orders(
+        &mut self,
+        orders: Vec<(AccountHash, U256, U256)>,
+        proof: Vec<[u8; 32]>,
+        proof_flags: Vec<bool>,
+    ) {
+        self._assert_status(&[
+            AuctionStatus::TokenDeposited,
+            AuctionStatus::Live,
+            AuctionStatus::Ended,
+        ]);
+        let leaves: Vec<[u8; 32]> = orders
+            .iter()
+            .map(|(account, tier, _)| merkle_tree::leaf(*account, *tier))
+            .collect();
+        self._assert_multi_proof(&leaves, &proof, &proof_flags);
+        for (account, tier, order_amount) in orders {
+            let new_order_amount = self.order(account).checked_add(order_amount).unwrap();
+            if tier.lt(&new_order_amount) {
+                runtime::revert(Error::OutOfTier);
             }
+            self._import_order(account, order_amount);
+        }
+    }
+
+    /// Register whitelisted accounts proven together by one multiproof against the current root,
+    /// `accounts` are `(account, tier)` in the leaf order of the multiproof. Registered accounts
+    /// order without a proof while their root is current or within its grace period after being
+    /// replaced. Only admin call.
+    fn register_whitelist(
+        &mut self,
+        accounts: Vec<(AccountHash, U256)>,
+        proof: Vec<[u8; 32]>,
+        proof_flags: Vec<bool>,
+    ) {
+        self._assert_status(NOT_FINALIZED);
+        let leaves: Vec<[u8; 32]> = accounts
+            .iter()
+            .map(|(account, tier)| merkle_tree::leaf(*account, *tier))
+            .collect();
+        self._assert_multi_proof(&leaves, &proof, &proof_flags);
+        let merkle_root = self.merkle_root();
+        let registrations = Registrations::instance();
+        for (account, tier) in accounts {
+            registrations.set(&Key::from(account), tier, merkle_root);
+        }
+    }
 
-            Orders::instance().set(&Key::from(account), unchecked_new_order_amount);
+    /// Tier registered for `account` against the current root
+    fn registered_tier(&self, account: AccountHash) -> Option<U256> {
+        Registrations::instance()
+            .get(&Key::from(account))
+            .filter(|(_, merkle_root)| self._is_live_root(merkle_root))
+            .map(|(tier, _)| tier)
+    }
+
+    /// Record an admin imported order, imported orders carry no payment so they are never
+    /// partially filled
+    fn _import_order(&mut self, account: AccountHash, order_amount: U256) {
+        if !self.oversubscription() && order_amount.gt(&self.remaining_capacity()) {
+            runtime::revert(Error::OutOfCapacity);
+        }
+        self._record_order(account, order_amount);
+    }
+
+    fn _record_order(&mut self, account: AccountHash, order_amount: U256) {
+        let exist_order_amount = Orders::instance()
+            .get(&Key::from(account))
+            .unwrap_or(U256::zero());
+        let unchecked_new_order_amount = exist_order_amount.checked_add(order_amount).unwrap();
+
+        if exist_order_amount.eq(&U256::zero()) {
+            self.increase_sold_amount_and_participants(order_amount);
+        } else {
+            self._increase_sold_amount(order_amount);
+        }
+
+        Orders::instance().set(&Key::from(account), unchecked_new_order_amount);
+        self.emit(CasperIdoEvent::OrderCreated {
+            account,
+            amount: order_amount,
         });
     }
 
-    /// `price` is given in 1TK=2 * 10 ** 9 CSPR format
-    fn change_auction_token_price(&mut self, price: U256) {
+    /// Enable or disable partial fill, the last buyer gets whatever is left of the capacity.
+    fn set_partial_fill(&mut self, partial_fill: bool) {
+        self._assert_status(&[AuctionStatus::Created, AuctionStatus::TokenDeposited]);
+        set_partial_fill(partial_fill);
+    }
+
+    /// The price is `price / denominator` whole pay tokens per whole auction token,
+    /// without denominator it is given in 1TK=2 * 10 ** 9 CSPR format
+    fn change_auction_token_price(&mut self, price: U256, denominator: Option<U256>) {
+        self._assert_status(NOT_FINALIZED);
+        let denominator = denominator.unwrap_or_else(|| U256::exp10(PRICE_DECIMALS.into()));
+        self._assert_valid_auction_token_price(price, denominator);
         set_auction_token_price(price);
+        set_auction_token_price_denominator(denominator);
+        self.emit(CasperIdoEvent::PriceChanged {
+            auction_token_price: price,
+            auction_token_price_denominator: denominator,
+        });
     }
 
+    /// Auction times are locked after finalized, schedules are frozen once any claim happened
     fn change_time_schedules(
         &mut self,
         auction_start_time: Time,
         auction_end_time: Time,
         schedules: Schedules,
     ) {
+        self._assert_status(&[
+            AuctionStatus::Created,
+            AuctionStatus::TokenDeposited,
+            AuctionStatus::Live,
+            AuctionStatus::Ended,
+            AuctionStatus::Finalized,
+        ]);
+        if get_claiming_started() {
+            runtime::revert(Error::SchedulesFrozen);
+        }
+        if self.status().eq(&AuctionStatus::Finalized)
+            && (auction_start_time.ne(&get_auction_start_time())
+                || auction_end_time.ne(&get_auction_end_time()))
+        {
+            runtime::revert(Error::InvalidStatus);
+        }
+        self._assert_valid_auction_time(auction_start_time, auction_end_time);
+        self._assert_valid_schedules(&schedules, auction_end_time);
+
         set_auction_start_time(auction_start_time);
         set_auction_end_time(auction_end_time);
-        set_schedules(schedules);
+        set_schedules(schedules.clone());
+        self._assert_valid_claim_deadline();
+        IFactory::new(self.factory()).update_auction_times(auction_start_time, auction_end_time);
+        self.emit(CasperIdoEvent::SchedulesChanged {
+            auction_start_time,
+            auction_end_time,
+            schedules,
+        });
     }
 
     fn set_treasury_wallet(&mut self, treasury_wallet: AccountHash) {
+        self._assert_status(&[
+            AuctionStatus::Created,
+            AuctionStatus::TokenDeposited,
+            AuctionStatus::Live,
+            AuctionStatus::Ended,
+            AuctionStatus::Finalized,
+            AuctionStatus::Failed,
+        ]);
         _set_treasury_wallet(treasury_wallet);
+        self.emit(CasperIdoEvent::TreasuryChanged { treasury_wallet });
     }
 
     fn treasury_wallet(&self) -> AccountHash {
         _get_treasury_wallet()
     }
 
+    /// Main pay token, order amounts are kept in its units
     fn pay_token(&self) -> Option<ContractHash> {
         get_pay_token()
     }
 
+    /// Every accepted pay token, the main `pay_token` first
+    fn pay_tokens(&self) -> Vec<Option<ContractHash>> {
+        let mut pay_tokens = vec![self.pay_token()];
+        pay_tokens.extend(get_pay_tokens());
+        pay_tokens
+    }
+
+    /// Accept `pay_token` besides the main pay token, priced like `change_auction_token_price`.
+    /// Only before the sale starts.
+    fn add_pay_token(
+        &mut self,
+        pay_token: Option<ContractHash>,
+        price: U256,
+        denominator: Option<U256>,
+    ) {
+        self._assert_status(&[AuctionStatus::Created, AuctionStatus::TokenDeposited]);
+        let denominator = denominator.unwrap_or_else(|| U256::exp10(PRICE_DECIMALS.into()));
+        self._assert_valid_auction_token_price(price, denominator);
+        if pay_token.eq(&self.pay_token()) {
+            runtime::revert(Error::InvalidPayToken);
+        }
+
+        let mut pay_tokens = get_pay_tokens();
+        if !pay_tokens.contains(&pay_token) {
+            pay_tokens.push(pay_token);
+            set_pay_tokens(pay_tokens);
+        }
+        PayTokenPrices::instance().set(
+            &pay_token,
+            price,
+            denominator,
+            self._pay_token_decimals(pay_token),
+        );
+    }
+
+    /// Price, price denominator and decimals of `pay_token`
+    fn pay_token_price(&self, pay_token: Option<ContractHash>) -> (U256, U256, u8) {
+        if pay_token.eq(&self.pay_token()) {
+            return (
+                self.auction_token_price(),
+                get_auction_token_price_denominator(),
+                get_pay_token_decimals(),
+            );
+        }
+        PayTokenPrices::instance()
+            .get(&pay_token)
+            .unwrap_or_revert_with(Error::InvalidPayToken)
+    }
+
+    /// Price of one whole auction token in the smallest unit of `pay_token`,
+    /// as numerator and denominator
+    fn _pay_unit_price(&self, pay_token: Option<ContractHash>) -> (U256, U256) {
+        let (price, denominator, decimals) = self.pay_token_price(pay_token);
+        (
+            price
+                .checked_mul(U256::exp10(decimals.into()))
+                .unwrap_or_revert(),
+            denominator,
+        )
+    }
+
+    fn _pay_token_decimals(&self, pay_token: Option<ContractHash>) -> u8 {
+        match pay_token {
+            Some(token) => IERC20::new(token).decimals(),
+            None => CSPR_DECIMALS,
+        }
+    }
+
+    /// Ratio converting a payment in `pay_token` to main pay token units through the prices,
+    /// as numerator and denominator. A round price scales it by the auction price over the
+    /// round price, so the order amount always counts auction tokens at the auction price.
+    fn _order_ratio(
+        &self,
+        pay_token: Option<ContractHash>,
+        round_price: Option<(U256, U256)>,
+    ) -> (U256, U256) {
+        let (main_numerator, main_denominator) = self._pay_unit_price(self.pay_token());
+        let (numerator, denominator) = self._pay_unit_price(pay_token);
+        let (price_numerator, price_denominator) = match round_price {
+            Some((price, price_denominator)) => (
+                self.auction_token_price()
+                    .checked_mul(price_denominator)
+                    .unwrap_or_revert(),
+                get_auction_token_price_denominator()
+                    .checked_mul(price)
+                    .unwrap_or_revert(),
+            ),
+            None => (U256::one(), U256::one()),
+        };
+        (
+            main_numerator
+                .checked_mul(denominator)
+                .unwrap_or_revert()
+                .checked_mul(price_numerator)
+                .unwrap_or_revert(),
+            main_denominator
+                .checked_mul(numerator)
+                .unwrap_or_revert()
+                .checked_mul(price_denominator)
+                .unwrap_or_revert(),
+        )
+    }
+
+    /// `pay_amount` of `pay_token` converted to main pay token units through the prices
+    fn _to_order_amount(
+        &self,
+        pay_amount: U256,
+        pay_token: Option<ContractHash>,
+        round_price: Option<(U256, U256)>,
+    ) -> U256 {
+        let (numerator, denominator) = self._order_ratio(pay_token, round_price);
+        pay_amount
+            .checked_mul(numerator)
+            .unwrap_or_revert()
+            .checked_div(denominator)
+            .unwrap_or_revert()
+    }
+
+    /// Fill `amount` of `pay_token` at the round price if any,
+    /// returns the accepted pay amount and its order amount
+    fn _fill_payment(
+        &self,
+        pay_token: Option<ContractHash>,
+        amount: U256,
+        round_price: Option<(U256, U256)>,
+    ) -> (U256, U256) {
+        let requested_amount = self._to_order_amount(amount, pay_token, round_price);
+        let order_amount = self._fill_order_amount(requested_amount);
+        if order_amount.eq(&requested_amount) {
+            return (amount, order_amount);
+        }
+
+        let (numerator, denominator) = self._order_ratio(pay_token, round_price);
+        let pay_amount = order_amount
+            .checked_mul(denominator)
+            .unwrap_or_revert()
+            .checked_div(numerator)
+            .unwrap_or_revert();
+        (
+            pay_amount,
+            self._to_order_amount(pay_amount, pay_token, round_price),
+        )
+    }
+
+    /// Amount paid by `account` in `pay_token`
+    fn payment(&self, account: AccountHash, pay_token: Option<ContractHash>) -> U256 {
+        Payments::instance()
+            .get(&Key::from(account), &pay_token)
+            .unwrap_or_default()
+    }
+
+    /// Amount escrowed in `pay_token`
+    fn raised_amount(&self, pay_token: Option<ContractHash>) -> U256 {
+        Raised::instance().get(&pay_token).unwrap_or_default()
+    }
+
+    fn _add_payment(&self, account: AccountHash, pay_token: Option<ContractHash>, amount: U256) {
+        Payments::instance().set(
+            &Key::from(account),
+            &pay_token,
+            self.payment(account, pay_token)
+                .checked_add(amount)
+                .unwrap_or_revert(),
+        );
+        Raised::instance().set(
+            &pay_token,
+            self.raised_amount(pay_token)
+                .checked_add(amount)
+                .unwrap_or_revert(),
+        );
+    }
+
+    /// Return `numerator / denominator` of every payment of `account`
+    fn _refund_payments(&self, account: AccountHash, numerator: U256, denominator: U256) {
+        for pay_token in self.pay_tokens() {
+            let payment = self.payment(account, pay_token);
+            let refund_amount = payment
+                .checked_mul(numerator)
+                .unwrap_or_revert()
+                .checked_div(denominator)
+                .unwrap_or_revert();
+            if refund_amount.is_zero() {
+                continue;
+            }
+
+            Payments::instance().set(
+                &Key::from(account),
+                &pay_token,
+                payment.checked_sub(refund_amount).unwrap_or_revert(),
+            );
+            Raised::instance().set(
+                &pay_token,
+                self.raised_amount(pay_token)
+                    .checked_sub(refund_amount)
+                    .unwrap_or_revert(),
+            );
+            self._transfer_payment(pay_token, account, refund_amount);
+        }
+    }
+
     fn creator(&self) -> AccountHash {
         get_creator()
     }
@@ -283,10 +1186,144 @@ pub trait CasperIdo<Storage: ContractStorage>: ContractContext<Storage> {
         get_auction_token_price()
     }
 
-    fn merkle_root(&self) -> String {
+    fn merkle_root(&self) -> [u8; 32] {
         _get_merkle_root()
     }
 
+    fn merkle_format(&self) -> MerkleFormat {
+        get_merkle_format()
+    }
+
+    /// Reverts unless `proof` shows `account` with `tier` is in the whitelist,
+    /// the proof must be in the format of the current root
+    fn _assert_whitelisted(&self, account: AccountHash, tier: U256, proof: MerkleProof) {
+        self._assert_not_revoked(&merkle_tree::leaf(account, tier));
+        if self.registered_tier(account) == Some(tier) {
+            return;
+        }
+        let mut result = self._verify_proof(self.merkle_root(), account, tier, &proof);
+        // Proofs against a superseded root are accepted until it expires
+        if matches!(result, Err(Error::NotWhiteListed)) {
+            for record in self.merkle_root_history() {
+                result = self._verify_proof(record.merkle_root, account, tier, &proof);
+                if result.is_ok() {
+                    break;
+                }
+            }
+        }
+        result.unwrap_or_revert();
+    }
+
+    /// Verify `proof` against `merkle_root` in the current format
+    fn _verify_proof(
+        &self,
+        merkle_root: [u8; 32],
+        account: AccountHash,
+        tier: U256,
+        proof: &MerkleProof,
+    ) -> Result<(), Error> {
+        match (self.merkle_format(), proof) {
+            (MerkleFormat::Sorted, MerkleProof::Sorted(proof)) => {
+                merkle_tree::verify(merkle_root, merkle_tree::leaf(account, tier), proof)
+            }
+            (MerkleFormat::Legacy, MerkleProof::Legacy(proof)) => merkle_tree::verify_legacy(
+                merkle_root,
+                merkle_tree::legacy_leaf(account, tier),
+                proof.clone(),
+            ),
+            _ => Err(Error::MalformedProof),
+        }
+    }
+
+    /// Current root or a superseded one which has not expired
+    fn _is_live_root(&self, merkle_root: &[u8; 32]) -> bool {
+        merkle_root.eq(&self.merkle_root())
+            || self
+                .merkle_root_history()
+                .iter()
+                .any(|record| record.merkle_root.eq(merkle_root))
+    }
+
+    fn _assert_not_revoked(&self, leaf: &[u8; 32]) {
+        if self.is_leaf_revoked(leaf) {
+            runtime::revert(Error::LeafRevoked);
+        }
+    }
+
+    /// Reverts unless `account` with `tier` may order now, in the round `round_id` when given
+    /// or with the auction whitelist otherwise. Returns the round.
+    fn _assert_can_order(
+        &self,
+        account: AccountHash,
+        tier: U256,
+        proof: MerkleProof,
+        round_id: Option<&String>,
+    ) -> Option<Round> {
+        let round_id = match round_id {
+            Some(round_id) => round_id,
+            None => {
+                self._assert_whitelisted(account, tier, proof);
+                return None;
+            }
+        };
+        let round = self
+            .round(round_id)
+            .unwrap_or_revert_with(Error::UnknownRound);
+        if !round.is_open(Time::from(runtime::get_blocktime())) {
+            runtime::revert(Error::OutOfRoundTime);
+        }
+        self._assert_not_revoked(&merkle_tree::leaf(account, tier));
+        // Rounds only use the sorted format
+        let result = match proof {
+            MerkleProof::Sorted(proof) => {
+                merkle_tree::verify(round.merkle_root, merkle_tree::leaf(account, tier), &proof)
+            }
+            MerkleProof::Legacy(_) => Err(Error::MalformedProof),
+        };
+        result.unwrap_or_revert();
+        Some(round)
+    }
+
+    /// Reverts unless all `leaves` are in the whitelist by a single multiproof,
+    /// only the sorted format supports multiproofs
+    fn _assert_multi_proof(&self, leaves: &[[u8; 32]], proof: &[[u8; 32]], proof_flags: &[bool]) {
+        if self.merkle_format().ne(&MerkleFormat::Sorted) {
+            runtime::revert(Error::MalformedProof);
+        }
+        for leaf in leaves {
+            self._assert_not_revoked(leaf);
+        }
+        merkle_tree::multi_proof_verify(self.merkle_root(), leaves, proof, proof_flags)
+            .unwrap_or_revert();
+    }
+
+    fn contract_purse(&self) -> URef {
+        _get_contract_purse()
+    }
+
+    /// Send `amount` of pay token or CSPR out of the contract to `recipient`
+    fn _transfer_payment(
+        &self,
+        pay_token: Option<ContractHash>,
+        recipient: AccountHash,
+        amount: U256,
+    ) {
+        match pay_token {
+            Some(token) => {
+                IERC20::new(token).transfer(Address::from(recipient), amount);
+            }
+            None => {
+                system::transfer_from_purse_to_account(
+                    self.contract_purse(),
+                    recipient,
+                    u256_to_512(&amount).unwrap_or_revert(),
+                    None,
+                )
+                .unwrap_or_revert();
+            }
+        }
+    }
+
     fn increase_sold_amount_and_participants(&self, amount: U256) {
         self._increase_sold_amount(amount);
         self._increase_total_participants();
@@ -296,6 +1333,10 @@ pub trait CasperIdo<Storage: ContractStorage>: ContractContext<Storage> {
         self.set_sold_amount(self.sold_amount().checked_add(amount).unwrap());
     }
 
+    fn _decrease_sold_amount(&self, amount: U256) {
+        self.set_sold_amount(self.sold_amount().checked_sub(amount).unwrap());
+    }
+
     fn set_sold_amount(&self, amount: U256) {
         _set_sold_amount(amount);
     }
@@ -308,6 +1349,10 @@ pub trait CasperIdo<Storage: ContractStorage>: ContractContext<Storage> {
         self.set_total_participants(self.total_participants() + 1);
     }
 
+    fn _decrease_total_participants(&self) {
+        self.set_total_participants(self.total_participants() - 1);
+    }
+
     fn set_total_participants(&self, total_participants: u64) {
         _set_total_participants(total_participants);
     }
@@ -320,6 +1365,270 @@ pub trait CasperIdo<Storage: ContractStorage>: ContractContext<Storage> {
         get_auction_token_capacity()
     }
 
+    fn soft_cap(&self) -> U256 {
+        get_soft_cap()
+    }
+
+    /// Current lifecycle status, `Live`, `Ended` and an unlocked `Failed` follow the auction times
+    fn status(&self) -> AuctionStatus {
+        let status = get_status();
+        if status.ne(&AuctionStatus::TokenDeposited) {
+            return status;
+        }
+
+        let time = Time::from(runtime::get_blocktime());
+        if !time.gt(&get_auction_start_time()) {
+            AuctionStatus::TokenDeposited
+        } else if time.lt(&get_auction_end_time()) {
+            AuctionStatus::Live
+        } else if self.sold_amount().lt(&self.soft_cap()) {
+            AuctionStatus::Failed
+        } else {
+            AuctionStatus::Ended
+        }
+    }
+
+    /// Sale ended below the soft cap
+    fn is_failed(&self) -> bool {
+        self.status().eq(&AuctionStatus::Failed)
+    }
+
+    /// Send the deposited auction token back to creator
+    fn _return_auction_token(&self) {
+        self._assert_auction_token_setted();
+        if get_auction_token_withdrawn() {
+            runtime::revert(Error::AlreadyWithdrawn);
+        }
+        set_auction_token_withdrawn(true);
+        IERC20::new(self.auction_token())
+            .transfer(Address::from(self.creator()), self.auction_token_capacity());
+    }
+
+    fn vesting(&self) -> Option<Vesting> {
+        get_vesting()
+    }
+
+    /// Auction token amount claimed by `account`
+    fn claimed_amount(&self, account: AccountHash) -> U256 {
+        Claimed::instance()
+            .get(&Key::from(account))
+            .unwrap_or_default()
+    }
+
+    /// Pay amount ordered by `account`, zero without order
+    fn order(&self, account: AccountHash) -> U256 {
+        Orders::instance()
+            .get(&Key::from(account))
+            .unwrap_or_default()
+    }
+
+    /// Whether `account` claimed the schedule at `schedule_time`
+    fn is_claimed(&self, account: AccountHash, schedule_time: Time) -> bool {
+        Claims::instance()
+            .get(&Key::from(account), schedule_time)
+            .unwrap_or_default()
+    }
+
+    /// Auction token amount `account` can claim now, zero before finalized
+    fn claimable_amount(&self, account: AccountHash) -> U256 {
+        if self.status().ne(&AuctionStatus::Finalized) {
+            return U256::zero();
+        }
+        let entitlement = self.entitlement(account);
+        if entitlement.is_zero() {
+            return U256::zero();
+        }
+
+        let unlocked_amount = match get_vesting() {
+            Some(vesting) => {
+                let current_block_time = Time::from(runtime::get_blocktime());
+                vesting.vested_amount(entitlement, current_block_time)
+            }
+            None => {
+                let (_, matured_percent) = self._claimable_schedules(account);
+                let unlocked_percent = self
+                    ._claimed_percent(account)
+                    .checked_add(matured_percent)
+                    .unwrap_or_revert();
+                self._unlocked_amount(entitlement, unlocked_percent)
+            }
+        };
+        unlocked_amount.saturating_sub(self.claimed_amount(account))
+    }
+
+    fn auction_info(&self) -> AuctionInfo {
+        AuctionInfo {
+            creator: self.creator(),
+            auction_start_time: get_auction_start_time(),
+            auction_end_time: get_auction_end_time(),
+            auction_token: self.auction_token(),
+            auction_token_price: self.auction_token_price(),
+            auction_token_price_denominator: get_auction_token_price_denominator(),
+            auction_token_capacity: self.auction_token_capacity(),
+            soft_cap: self.soft_cap(),
+            pay_token: self.pay_token(),
+            sold_amount: self.sold_amount(),
+            total_participants: self.total_participants(),
+            status: self.status(),
+        }
+    }
+
+    /// Auction token amount claimed by every user
+    fn total_claimed_amount(&self) -> U256 {
+        get_total_claimed()
+    }
+
+    /// Transfer claimed auction token and track the claimed amounts
+    fn _transfer_claimed(&mut self, caller: AccountHash, amount: U256) {
+        Claimed::instance().set(
+            &Key::from(caller),
+            self.claimed_amount(caller)
+                .checked_add(amount)
+                .unwrap_or_revert(),
+        );
+        set_total_claimed(
+            self.total_claimed_amount()
+                .checked_add(amount)
+                .unwrap_or_revert(),
+        );
+        IERC20::new(self.auction_token()).transfer(Address::from(caller), amount);
+        self.emit(CasperIdoEvent::Claimed {
+            account: caller,
+            amount,
+        });
+    }
+
+    /// Pay amount converted to auction token through `auction_token_price`
+    /// and the decimals of both tokens
+    fn auction_token_amount(&self, pay_amount: U256) -> U256 {
+        let auction_token_decimals = get_auction_token_decimals();
+        let (numerator, denominator) = self._pay_unit_price(self.pay_token());
+        pay_amount
+            .checked_mul(U256::exp10(auction_token_decimals.into()))
+            .unwrap_or_revert()
+            .checked_mul(denominator)
+            .unwrap_or_revert()
+            .checked_div(numerator)
+            .unwrap_or_revert()
+    }
+
+    fn oversubscription(&self) -> bool {
+        get_oversubscription()
+    }
+
+    /// Part of `order_amount` that is filled,
+    /// an oversubscribed sale fills `order * capacity / total_raised`
+    fn filled_amount(&self, order_amount: U256) -> U256 {
+        let sold_amount = self.sold_amount();
+        let capacity = self.auction_token_capacity_in_pay_amount();
+        if !self.oversubscription() || sold_amount.le(&capacity) {
+            return order_amount;
+        }
+        order_amount
+            .checked_mul(capacity)
+            .unwrap_or_revert()
+            .checked_div(sold_amount)
+            .unwrap_or_revert()
+    }
+
+    /// Unfilled part of `amount` in an oversubscribed sale. Rounded down, so the refunds
+    /// of every account together never exceed the unfilled part of the escrow.
+    fn _unfilled_amount(&self, amount: U256) -> U256 {
+        let sold_amount = self.sold_amount();
+        if !self.oversubscription() || sold_amount.is_zero() {
+            return U256::zero();
+        }
+        amount
+            .checked_mul(
+                sold_amount
+                    .checked_sub(self.total_filled_amount())
+                    .unwrap_or_revert(),
+            )
+            .unwrap_or_revert()
+            .checked_div(sold_amount)
+            .unwrap_or_revert()
+    }
+
+    /// Sum of every filled order
+    fn total_filled_amount(&self) -> U256 {
+        core::cmp::min(
+            self.sold_amount(),
+            self.auction_token_capacity_in_pay_amount(),
+        )
+    }
+
+    fn partial_fill(&self) -> bool {
+        get_partial_fill()
+    }
+
+    /// `auction_token_capacity` converted to pay amount through `auction_token_price`
+    fn auction_token_capacity_in_pay_amount(&self) -> U256 {
+        let auction_token_decimals = get_auction_token_decimals();
+        let (numerator, denominator) = self._pay_unit_price(self.pay_token());
+        self.auction_token_capacity()
+            .checked_mul(numerator)
+            .unwrap_or_revert()
+            .checked_div(
+                U256::exp10(auction_token_decimals.into())
+                    .checked_mul(denominator)
+                    .unwrap_or_revert(),
+            )
+            .unwrap_or_revert()
+    }
+
+    /// Pay amount which can still be ordered before reaching the capacity
+    fn remaining_capacity(&self) -> U256 {
+        self.auction_token_capacity_in_pay_amount()
+            .saturating_sub(self.sold_amount())
+    }
+
+    /// Returns the part of `amount` that can be filled.
+    /// Reverts with `Error::OutOfCapacity` unless partial fill is enabled and capacity is left.
+    fn _fill_order_amount(&self, amount: U256) -> U256 {
+        // Oversubscribed sale takes every order and allocates pro-rata after the sale
+        if self.oversubscription() {
+            return amount;
+        }
+        let remaining_capacity = self.remaining_capacity();
+        if amount.le(&remaining_capacity) {
+            return amount;
+        }
+        if !self.partial_fill() || remaining_capacity.is_zero() {
+            runtime::revert(Error::OutOfCapacity);
+        }
+        remaining_capacity
+    }
+
+    /// Check tier, or the allocation in the round, and record the order
+    fn _add_order(
+        &mut self,
+        caller: AccountHash,
+        tier: U256,
+        order_amount: U256,
+        round: Option<(String, Round)>,
+    ) {
+        match round {
+            // Check order amount is less than tier
+            None => {
+                let new_order_amount = self.order(caller).checked_add(order_amount).unwrap();
+                if tier.lt(&new_order_amount) {
+                    runtime::revert(Error::OutOfTier);
+                }
+            }
+            Some((round_id, round)) => {
+                let new_round_order_amount = self
+                    .round_order(caller, &round_id)
+                    .checked_add(order_amount)
+                    .unwrap_or_revert();
+                if round.allocation(tier).lt(&new_round_order_amount) {
+                    runtime::revert(Error::OutOfTier);
+                }
+                RoundOrders::instance().set(&Key::from(caller), &round_id, new_round_order_amount);
+            }
+        }
+        self._record_order(caller, order_amount);
+    }
+
     fn _assert_null_auction_token(&self) {
         let auction_token = self.auction_token();
         if auction_token.ne(&ContractHash::new([0u8; 32])) {
@@ -327,16 +1636,71 @@ pub trait CasperIdo<Storage: ContractStorage>: ContractContext<Storage> {
         }
     }
 
-    fn _assert_auction_time(&self) {
-        let time = Time::from(runtime::get_blocktime());
-        let auction_start_time = get_auction_start_time();
-        let auction_end_time = get_auction_end_time();
-        if !(time.gt(&auction_start_time) && time.lt(&auction_end_time)) {
-            runtime::revert(Error::InvalidTime);
+    fn _assert_auction_token_setted(&self) {
+        let auction_token = self.auction_token();
+        if auction_token.eq(&ContractHash::new([0u8; 32])) {
+            runtime::revert(Error::AuctionTokenNotSetted);
+        }
+    }
+
+    /// The claim deadline, if any, must come after every schedule and the end of vesting
+    fn _assert_valid_claim_deadline(&self) {
+        let claim_deadline = match self.claim_deadline() {
+            Some(claim_deadline) => claim_deadline,
+            None => return,
+        };
+        let mut last_unlock_time = get_schedules().keys().last().copied().unwrap_or_default();
+        if let Some(vesting) = get_vesting() {
+            last_unlock_time = core::cmp::max(last_unlock_time, vesting.end_time());
+        }
+        if !last_unlock_time.lt(&claim_deadline) {
+            runtime::revert(Error::InvalidClaimDeadline);
+        }
+    }
+
+    fn _assert_before_claim_deadline(&self) {
+        if let Some(claim_deadline) = self.claim_deadline() {
+            if !Time::from(runtime::get_blocktime()).lt(&claim_deadline) {
+                runtime::revert(Error::ClaimDeadlinePassed);
+            }
+        }
+    }
+
+    fn _assert_valid_auction_time(&self, auction_start_time: Time, auction_end_time: Time) {
+        if !auction_start_time.lt(&auction_end_time) {
+            runtime::revert(Error::InvalidAuctionTime);
+        }
+    }
+
+    fn _assert_valid_auction_token_price(&self, auction_token_price: U256, denominator: U256) {
+        if auction_token_price.is_zero() || denominator.is_zero() {
+            runtime::revert(Error::InvalidAuctionTokenPrice);
+        }
+    }
+
+    /// Percents must add up to 10000 and no schedule can unlock before the sale ends
+    fn _assert_valid_schedules(&self, schedules: &Schedules, auction_end_time: Time) {
+        let mut total_percent = U256::zero();
+        for (schedule_time, schedule_percent) in schedules {
+            if schedule_time.lt(&auction_end_time) {
+                runtime::revert(Error::ScheduleBeforeAuctionEnd);
+            }
+            total_percent = total_percent
+                .checked_add(*schedule_percent)
+                .unwrap_or_revert();
+        }
+        if total_percent.ne(&U256::exp10(4)) {
+            runtime::revert(Error::InvalidSchedulePercent);
+        }
+    }
+
+    fn _assert_status(&self, allowed: &[AuctionStatus]) {
+        if !allowed.contains(&self.status()) {
+            runtime::revert(Error::InvalidStatus);
         }
     }
 
     fn emit(&mut self, event: CasperIdoEvent) {
-        event::emit(&event);
+        event::emit(self.contract_package_hash(), &event);
     }
 }
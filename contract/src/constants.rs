@@ -1 +1,13 @@
 pub const DEFFAULT_MERKLE_TREE_KEY_NAME: &str = "default_merkle_root";
+
+/// Longest accepted merkle proof, enough for a whitelist of 2 ** 32 accounts
+pub const MAX_PROOF_LENGTH: usize = 32;
+
+/// Most superseded merkle roots kept for in-flight proofs
+pub const MAX_MERKLE_ROOT_HISTORY: usize = 8;
+
+/// Decimals of CSPR, 1 CSPR is 10 ** 9 motes
+pub const CSPR_DECIMALS: u8 = 9;
+
+/// Decimals of a price given without denominator, 2 * 10 ** 9 is 2 pay tokens per auction token
+pub const PRICE_DECIMALS: u8 = 9;
@@ -1,10 +1,15 @@
-use alloc::{string::String, vec::Vec};
-use casper_contract::unwrap_or_revert::UnwrapOrRevert;
-use casper_types::{account::AccountHash, ContractHash, Key, U256};
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{account::AccountHash, ContractHash, Key, URef, U256};
 use contract_utils::{get_key, key_and_value_to_str, key_to_str, set_key, Dict};
 
 use crate::{
-    structs::{Schedules, Time},
+    enums::{AuctionStatus, MerkleFormat},
+    structs::{AuctionRecord, MerkleRootRecord, Round, Schedules, Time, Vesting},
     Error,
 };
 
@@ -32,6 +37,10 @@ impl Orders {
     pub fn set(&self, account: &Key, value: U256) {
         self.dict.set(&key_to_str(account), value);
     }
+
+    pub fn remove(&self, account: &Key) {
+        self.dict.remove::<U256>(&key_to_str(account));
+    }
 }
 
 pub const CLAIMS_DICT: &str = "claims";
@@ -66,6 +75,201 @@ impl Claims {
     }
 }
 
+pub const CLAIMED_DICT: &str = "claimed";
+pub struct Claimed {
+    dict: Dict,
+}
+
+impl Claimed {
+    pub fn instance() -> Claimed {
+        Claimed {
+            dict: Dict::instance(CLAIMED_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(CLAIMED_DICT)
+    }
+
+    pub fn get(&self, account: &Key) -> Option<U256> {
+        self.dict.get(&key_to_str(account))
+    }
+
+    pub fn set(&self, account: &Key, amount: U256) {
+        self.dict.set(&key_to_str(account), amount);
+    }
+}
+
+/// Total auction token amount of each account, fixed on its first claim
+pub const ENTITLEMENTS_DICT: &str = "entitlements";
+pub struct Entitlements {
+    dict: Dict,
+}
+
+impl Entitlements {
+    pub fn instance() -> Entitlements {
+        Entitlements {
+            dict: Dict::instance(ENTITLEMENTS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(ENTITLEMENTS_DICT)
+    }
+
+    pub fn get(&self, account: &Key) -> Option<U256> {
+        self.dict.get(&key_to_str(account))
+    }
+
+    pub fn set(&self, account: &Key, amount: U256) {
+        self.dict.set(&key_to_str(account), amount);
+    }
+}
+
+/// Whitelist leaves revoked by admin, keyed by the hex of `merkle_tree::leaf`
+pub const REVOKED_LEAVES_DICT: &str = "revoked_leaves";
+pub struct RevokedLeaves {
+    dict: Dict,
+}
+
+impl RevokedLeaves {
+    pub fn instance() -> RevokedLeaves {
+        RevokedLeaves {
+            dict: Dict::instance(REVOKED_LEAVES_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(REVOKED_LEAVES_DICT)
+    }
+
+    pub fn get(&self, leaf: &[u8; 32]) -> Option<bool> {
+        self.dict.get(&hex::encode(leaf))
+    }
+
+    pub fn set(&self, leaf: &[u8; 32], revoked: bool) {
+        self.dict.set(&hex::encode(leaf), revoked);
+    }
+}
+
+/// Tier of an account proven by a multiproof and the root it was proven against
+pub const REGISTRATIONS_DICT: &str = "registrations";
+pub struct Registrations {
+    dict: Dict,
+}
+
+impl Registrations {
+    pub fn instance() -> Registrations {
+        Registrations {
+            dict: Dict::instance(REGISTRATIONS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(REGISTRATIONS_DICT)
+    }
+
+    pub fn get(&self, account: &Key) -> Option<(U256, [u8; 32])> {
+        self.dict.get(&key_to_str(account))
+    }
+
+    pub fn set(&self, account: &Key, tier: U256, merkle_root: [u8; 32]) {
+        self.dict.set(&key_to_str(account), (tier, merkle_root));
+    }
+}
+
+/// Amount ordered by an account in a round
+pub const ROUND_ORDERS_DICT: &str = "round_orders";
+pub struct RoundOrders {
+    dict: Dict,
+}
+
+impl RoundOrders {
+    pub fn instance() -> RoundOrders {
+        RoundOrders {
+            dict: Dict::instance(ROUND_ORDERS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(ROUND_ORDERS_DICT)
+    }
+
+    pub fn get(&self, account: &Key, round_id: &String) -> Option<U256> {
+        self.dict.get(&key_and_value_to_str(account, round_id))
+    }
+
+    pub fn set(&self, account: &Key, round_id: &String, amount: U256) {
+        self.dict
+            .set(&key_and_value_to_str(account, round_id), amount);
+    }
+
+    pub fn remove(&self, account: &Key, round_id: &String) {
+        self.dict
+            .remove::<U256>(&key_and_value_to_str(account, round_id));
+    }
+}
+
+pub const REFUND_CLAIMS_DICT: &str = "refund_claims";
+pub struct RefundClaims {
+    dict: Dict,
+}
+
+impl RefundClaims {
+    pub fn instance() -> RefundClaims {
+        RefundClaims {
+            dict: Dict::instance(REFUND_CLAIMS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(REFUND_CLAIMS_DICT)
+    }
+
+    pub fn get(&self, account: &Key) -> Option<bool> {
+        self.dict.get(&key_to_str(account))
+    }
+
+    pub fn set(&self, account: &Key, claimed: bool) {
+        self.dict.set(&key_to_str(account), claimed);
+    }
+}
+
+pub const EVENTS_DICT: &str = "events";
+pub struct Events {
+    dict: Dict,
+}
+
+impl Events {
+    pub fn instance() -> Events {
+        Events {
+            dict: Dict::instance(EVENTS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(EVENTS_DICT)
+    }
+
+    pub fn get(&self, index: u32) -> Option<BTreeMap<String, String>> {
+        self.dict.get(&index.to_string())
+    }
+
+    pub fn set(&self, index: u32, event: BTreeMap<String, String>) {
+        self.dict.set(&index.to_string(), event);
+    }
+}
+
+const EVENTS_LENGTH: &str = "events_length";
+
+pub fn set_events_length(length: u32) {
+    set_key(EVENTS_LENGTH, length);
+}
+
+pub fn get_events_length() -> u32 {
+    get_key(EVENTS_LENGTH).unwrap_or_default()
+}
+
 const CREATOR: &str = "creator";
 
 pub fn set_creator(creator: AccountHash) {
@@ -76,16 +280,56 @@ pub fn get_creator() -> AccountHash {
     get_key(CREATOR).unwrap_or_revert()
 }
 
+const STATUS: &str = "status";
+
+pub fn set_status(status: AuctionStatus) {
+    set_key(STATUS, status);
+}
+
+pub fn get_status() -> AuctionStatus {
+    get_key(STATUS).unwrap_or_revert()
+}
+
 const MERKLE_ROOT: &str = "merkle_root";
 
-pub fn _set_merkle_root(root: String) {
+pub fn _set_merkle_root(root: [u8; 32]) {
     set_key(MERKLE_ROOT, root);
 }
 
-pub fn _get_merkle_root() -> String {
+pub fn _get_merkle_root() -> [u8; 32] {
     get_key(MERKLE_ROOT).unwrap_or_revert_with(Error::InvalidMerkleRoot)
 }
 
+const MERKLE_ROOT_ACTIVATION_TIME: &str = "merkle_root_activation_time";
+
+pub fn set_merkle_root_activation_time(time: Time) {
+    set_key(MERKLE_ROOT_ACTIVATION_TIME, time);
+}
+
+pub fn get_merkle_root_activation_time() -> Time {
+    get_key(MERKLE_ROOT_ACTIVATION_TIME).unwrap_or_default()
+}
+
+const MERKLE_ROOT_HISTORY: &str = "merkle_root_history";
+
+pub fn set_merkle_root_history(history: Vec<MerkleRootRecord>) {
+    set_key(MERKLE_ROOT_HISTORY, history);
+}
+
+pub fn get_merkle_root_history() -> Vec<MerkleRootRecord> {
+    get_key(MERKLE_ROOT_HISTORY).unwrap_or_default()
+}
+
+const MERKLE_FORMAT: &str = "merkle_format";
+
+pub fn set_merkle_format(merkle_format: MerkleFormat) {
+    set_key(MERKLE_FORMAT, merkle_format);
+}
+
+pub fn get_merkle_format() -> MerkleFormat {
+    get_key(MERKLE_FORMAT).unwrap_or_revert()
+}
+
 const AUCTION_START_TIME: &str = "auction_start_time";
 
 pub fn set_auction_start_time(time: Time) {
@@ -126,6 +370,26 @@ pub fn get_auction_token_price() -> U256 {
     get_key(AUCTION_TOKEN_PRICE).unwrap_or_revert()
 }
 
+const AUCTION_TOKEN_PRICE_DENOMINATOR: &str = "auction_token_price_denominator";
+
+pub fn set_auction_token_price_denominator(denominator: U256) {
+    set_key(AUCTION_TOKEN_PRICE_DENOMINATOR, denominator);
+}
+
+pub fn get_auction_token_price_denominator() -> U256 {
+    get_key(AUCTION_TOKEN_PRICE_DENOMINATOR).unwrap_or_revert()
+}
+
+const PAY_TOKEN_DECIMALS: &str = "pay_token_decimals";
+
+pub fn set_pay_token_decimals(decimals: u8) {
+    set_key(PAY_TOKEN_DECIMALS, decimals);
+}
+
+pub fn get_pay_token_decimals() -> u8 {
+    get_key(PAY_TOKEN_DECIMALS).unwrap_or_revert()
+}
+
 const AUCTION_TOKEN_CAPACITY: &str = "auction_token_capacity";
 
 pub fn set_auction_token_capacity(capacity: U256) {
@@ -136,6 +400,317 @@ pub fn get_auction_token_capacity() -> U256 {
     get_key(AUCTION_TOKEN_CAPACITY).unwrap_or_revert()
 }
 
+const AUCTION_TOKEN_DECIMALS: &str = "auction_token_decimals";
+
+pub fn set_auction_token_decimals(decimals: u8) {
+    set_key(AUCTION_TOKEN_DECIMALS, decimals);
+}
+
+pub fn get_auction_token_decimals() -> u8 {
+    get_key(AUCTION_TOKEN_DECIMALS).unwrap_or_revert_with(Error::AuctionTokenNotSetted)
+}
+
+const SOFT_CAP: &str = "soft_cap";
+
+pub fn set_soft_cap(soft_cap: U256) {
+    set_key(SOFT_CAP, soft_cap);
+}
+
+pub fn get_soft_cap() -> U256 {
+    get_key(SOFT_CAP).unwrap_or_default()
+}
+
+const AUCTION_TOKEN_WITHDRAWN: &str = "auction_token_withdrawn";
+
+pub fn set_auction_token_withdrawn(withdrawn: bool) {
+    set_key(AUCTION_TOKEN_WITHDRAWN, withdrawn);
+}
+
+pub fn get_auction_token_withdrawn() -> bool {
+    get_key(AUCTION_TOKEN_WITHDRAWN).unwrap_or_default()
+}
+
+const UNSOLD_WITHDRAWN: &str = "unsold_withdrawn";
+
+pub fn set_unsold_withdrawn(withdrawn: bool) {
+    set_key(UNSOLD_WITHDRAWN, withdrawn);
+}
+
+pub fn get_unsold_withdrawn() -> bool {
+    get_key(UNSOLD_WITHDRAWN).unwrap_or_default()
+}
+
+const CLAIM_DEADLINE: &str = "claim_deadline";
+
+pub fn set_claim_deadline(claim_deadline: Option<Time>) {
+    set_key(CLAIM_DEADLINE, claim_deadline);
+}
+
+pub fn get_claim_deadline() -> Option<Time> {
+    get_key(CLAIM_DEADLINE).unwrap_or_default()
+}
+
+const SWEEP_WALLET: &str = "sweep_wallet";
+
+pub fn set_sweep_wallet(sweep_wallet: Option<AccountHash>) {
+    set_key(SWEEP_WALLET, sweep_wallet);
+}
+
+pub fn get_sweep_wallet() -> Option<AccountHash> {
+    get_key(SWEEP_WALLET).unwrap_or_default()
+}
+
+const UNCLAIMED_SWEPT: &str = "unclaimed_swept";
+
+pub fn set_unclaimed_swept(swept: bool) {
+    set_key(UNCLAIMED_SWEPT, swept);
+}
+
+pub fn get_unclaimed_swept() -> bool {
+    get_key(UNCLAIMED_SWEPT).unwrap_or_default()
+}
+
+const ROUNDS: &str = "rounds";
+
+pub fn set_rounds(rounds: BTreeMap<String, Round>) {
+    set_key(ROUNDS, rounds);
+}
+
+pub fn get_rounds() -> BTreeMap<String, Round> {
+    get_key(ROUNDS).unwrap_or_default()
+}
+
+const PARTIAL_FILL: &str = "partial_fill";
+
+pub fn set_partial_fill(partial_fill: bool) {
+    set_key(PARTIAL_FILL, partial_fill);
+}
+
+pub fn get_partial_fill() -> bool {
+    get_key(PARTIAL_FILL).unwrap_or_default()
+}
+
+const OVERSUBSCRIPTION: &str = "oversubscription";
+
+pub fn set_oversubscription(oversubscription: bool) {
+    set_key(OVERSUBSCRIPTION, oversubscription);
+}
+
+pub fn get_oversubscription() -> bool {
+    get_key(OVERSUBSCRIPTION).unwrap_or_default()
+}
+
+const FACTORY: &str = "factory";
+
+pub fn set_factory(factory: ContractHash) {
+    set_key(FACTORY, factory);
+}
+
+pub fn get_factory() -> ContractHash {
+    get_key(FACTORY).unwrap_or_revert()
+}
+
+const FEE_NUMERATOR: &str = "fee_numerator";
+
+pub fn set_fee_numerator(fee_numerator: U256) {
+    set_key(FEE_NUMERATOR, fee_numerator);
+}
+
+pub fn get_fee_numerator() -> U256 {
+    get_key(FEE_NUMERATOR).unwrap_or_default()
+}
+
+/// Factory `fee_denominator` when the numerator was set
+const FEE_DENOMINATOR: &str = "fee_denominator";
+
+pub fn set_fee_denominator(fee_denominator: U256) {
+    set_key(FEE_DENOMINATOR, fee_denominator);
+}
+
+pub fn get_fee_denominator() -> Option<U256> {
+    get_key(FEE_DENOMINATOR)
+}
+
+/// Dictionary key of a pay token, `None` is CSPR
+fn pay_token_to_str(pay_token: &Option<ContractHash>) -> String {
+    match pay_token {
+        Some(token) => key_to_str(&Key::from(*token)),
+        None => "cspr".to_string(),
+    }
+}
+
+pub const FEE_AMOUNTS_DICT: &str = "fee_amounts";
+pub struct FeeAmounts {
+    dict: Dict,
+}
+
+impl FeeAmounts {
+    pub fn instance() -> FeeAmounts {
+        FeeAmounts {
+            dict: Dict::instance(FEE_AMOUNTS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(FEE_AMOUNTS_DICT)
+    }
+
+    pub fn get(&self, pay_token: &Option<ContractHash>) -> Option<U256> {
+        self.dict.get(&pay_token_to_str(pay_token))
+    }
+
+    pub fn set(&self, pay_token: &Option<ContractHash>, amount: U256) {
+        self.dict.set(&pay_token_to_str(pay_token), amount);
+    }
+}
+
+const PAY_TOKENS: &str = "pay_tokens";
+
+/// Pay tokens accepted besides `pay_token`
+pub fn set_pay_tokens(pay_tokens: Vec<Option<ContractHash>>) {
+    set_key(PAY_TOKENS, pay_tokens);
+}
+
+pub fn get_pay_tokens() -> Vec<Option<ContractHash>> {
+    get_key(PAY_TOKENS).unwrap_or_default()
+}
+
+pub const PAY_TOKEN_PRICES_DICT: &str = "pay_token_prices";
+pub struct PayTokenPrices {
+    dict: Dict,
+}
+
+impl PayTokenPrices {
+    pub fn instance() -> PayTokenPrices {
+        PayTokenPrices {
+            dict: Dict::instance(PAY_TOKEN_PRICES_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(PAY_TOKEN_PRICES_DICT)
+    }
+
+    /// Price, price denominator and decimals of `pay_token`
+    pub fn get(&self, pay_token: &Option<ContractHash>) -> Option<(U256, U256, u8)> {
+        self.dict.get(&pay_token_to_str(pay_token))
+    }
+
+    pub fn set(
+        &self,
+        pay_token: &Option<ContractHash>,
+        price: U256,
+        denominator: U256,
+        decimals: u8,
+    ) {
+        self.dict
+            .set(&pay_token_to_str(pay_token), (price, denominator, decimals));
+    }
+}
+
+pub const PAYMENTS_DICT: &str = "payments";
+pub struct Payments {
+    dict: Dict,
+}
+
+impl Payments {
+    pub fn instance() -> Payments {
+        Payments {
+            dict: Dict::instance(PAYMENTS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(PAYMENTS_DICT)
+    }
+
+    pub fn get(&self, account: &Key, pay_token: &Option<ContractHash>) -> Option<U256> {
+        self.dict.get(&key_and_value_to_str(account, pay_token))
+    }
+
+    pub fn set(&self, account: &Key, pay_token: &Option<ContractHash>, amount: U256) {
+        self.dict
+            .set(&key_and_value_to_str(account, pay_token), amount);
+    }
+}
+
+pub const RAISED_DICT: &str = "raised";
+pub struct Raised {
+    dict: Dict,
+}
+
+impl Raised {
+    pub fn instance() -> Raised {
+        Raised {
+            dict: Dict::instance(RAISED_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(RAISED_DICT)
+    }
+
+    pub fn get(&self, pay_token: &Option<ContractHash>) -> Option<U256> {
+        self.dict.get(&pay_token_to_str(pay_token))
+    }
+
+    pub fn set(&self, pay_token: &Option<ContractHash>, amount: U256) {
+        self.dict.set(&pay_token_to_str(pay_token), amount);
+    }
+}
+
+/// Amount escrowed per pay token when the sale was finalized, before any refund claim
+pub const RAISED_AT_CLOSE_DICT: &str = "raised_at_close";
+pub struct RaisedAtClose {
+    dict: Dict,
+}
+
+impl RaisedAtClose {
+    pub fn instance() -> RaisedAtClose {
+        RaisedAtClose {
+            dict: Dict::instance(RAISED_AT_CLOSE_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(RAISED_AT_CLOSE_DICT)
+    }
+
+    pub fn get(&self, pay_token: &Option<ContractHash>) -> Option<U256> {
+        self.dict.get(&pay_token_to_str(pay_token))
+    }
+
+    pub fn set(&self, pay_token: &Option<ContractHash>, amount: U256) {
+        self.dict.set(&pay_token_to_str(pay_token), amount);
+    }
+}
+
+/// Payments at close of the accounts which claimed their refund, per pay token
+pub const REFUNDED_PAYMENTS_DICT: &str = "refunded_payments";
+pub struct RefundedPayments {
+    dict: Dict,
+}
+
+impl RefundedPayments {
+    pub fn instance() -> RefundedPayments {
+        RefundedPayments {
+            dict: Dict::instance(REFUNDED_PAYMENTS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(REFUNDED_PAYMENTS_DICT)
+    }
+
+    pub fn get(&self, pay_token: &Option<ContractHash>) -> Option<U256> {
+        self.dict.get(&pay_token_to_str(pay_token))
+    }
+
+    pub fn set(&self, pay_token: &Option<ContractHash>, amount: U256) {
+        self.dict.set(&pay_token_to_str(pay_token), amount);
+    }
+}
+
 const PAY_TOKEN: &str = "pay_token";
 
 pub fn set_pay_token(pay_token: Option<ContractHash>) {
@@ -156,6 +731,60 @@ pub fn get_schedules() -> Schedules {
     get_key(SCHEDULES).unwrap_or_revert()
 }
 
+const CONTRACT_PURSE: &str = "contract_purse";
+
+pub fn _set_contract_purse(purse: URef) {
+    runtime::put_key(CONTRACT_PURSE, Key::from(purse));
+}
+
+pub fn _get_contract_purse() -> URef {
+    *runtime::get_key(CONTRACT_PURSE)
+        .unwrap_or_revert()
+        .as_uref()
+        .unwrap_or_revert()
+}
+
+const FUNDS_WITHDRAWN: &str = "funds_withdrawn";
+
+pub fn set_funds_withdrawn(withdrawn: bool) {
+    set_key(FUNDS_WITHDRAWN, withdrawn);
+}
+
+pub fn get_funds_withdrawn() -> bool {
+    get_key(FUNDS_WITHDRAWN).unwrap_or_default()
+}
+
+/// Set by the first claim, even one transferring nothing
+const CLAIMING_STARTED: &str = "claiming_started";
+
+pub fn set_claiming_started(started: bool) {
+    set_key(CLAIMING_STARTED, started);
+}
+
+pub fn get_claiming_started() -> bool {
+    get_key(CLAIMING_STARTED).unwrap_or_revert()
+}
+
+const VESTING: &str = "vesting";
+
+pub fn set_vesting(vesting: Option<Vesting>) {
+    set_key(VESTING, vesting);
+}
+
+pub fn get_vesting() -> Option<Vesting> {
+    get_key(VESTING).unwrap_or_default()
+}
+
+const TOTAL_CLAIMED: &str = "total_claimed";
+
+pub fn set_total_claimed(amount: U256) {
+    set_key(TOTAL_CLAIMED, amount);
+}
+
+pub fn get_total_claimed() -> U256 {
+    get_key(TOTAL_CLAIMED).unwrap_or_default()
+}
+
 const TOTAL_PARTICIPANTS: &str = "total_participants";
 
 pub fn _set_total_participants(participants: u64) {
@@ -207,12 +836,82 @@ pub fn _get_fee_wallet() -> AccountHash {
     get_key(FEE_WALLET).unwrap_or_revert()
 }
 
-const AUCTIONS: &str = "auctions";
+pub const AUCTIONS_DICT: &str = "auctions";
+pub struct Auctions {
+    dict: Dict,
+}
+
+impl Auctions {
+    pub fn instance() -> Auctions {
+        Auctions {
+            dict: Dict::instance(AUCTIONS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(AUCTIONS_DICT)
+    }
+
+    pub fn get(&self, index: u32) -> Option<AuctionRecord> {
+        self.dict.get(&index.to_string())
+    }
+
+    pub fn set(&self, index: u32, auction: AuctionRecord) {
+        self.dict.set(&index.to_string(), auction);
+    }
+
+    pub fn remove(&self, index: u32) {
+        self.dict.remove::<AuctionRecord>(&index.to_string());
+    }
+}
+
+pub const AUCTION_INDEXES_DICT: &str = "auction_indexes";
+pub struct AuctionIndexes {
+    dict: Dict,
+}
+
+impl AuctionIndexes {
+    pub fn instance() -> AuctionIndexes {
+        AuctionIndexes {
+            dict: Dict::instance(AUCTION_INDEXES_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(AUCTION_INDEXES_DICT)
+    }
+
+    pub fn get(&self, auction_contract: &ContractHash) -> Option<u32> {
+        self.dict.get_by_key(&Key::from(*auction_contract))
+    }
+
+    pub fn set(&self, auction_contract: &ContractHash, index: u32) {
+        self.dict.set_by_key(&Key::from(*auction_contract), index);
+    }
+
+    pub fn remove(&self, auction_contract: &ContractHash) {
+        self.dict
+            .remove_by_key::<u32>(&Key::from(*auction_contract));
+    }
+}
+
+const AUCTIONS_LENGTH: &str = "auctions_length";
+
+pub fn _set_auctions_length(length: u32) {
+    set_key(AUCTIONS_LENGTH, length);
+}
+
+pub fn _get_auctions_length() -> u32 {
+    get_key(AUCTIONS_LENGTH).unwrap_or_default()
+}
+
+/// Removed auctions leave their index empty, so it runs ahead of the length
+const NEXT_AUCTION_INDEX: &str = "next_auction_index";
 
-pub fn _set_auctions(auctions: Vec<(ContractHash, Time, Time)>) {
-    set_key(AUCTIONS, auctions);
+pub fn _set_next_auction_index(index: u32) {
+    set_key(NEXT_AUCTION_INDEX, index);
 }
 
-pub fn _get_auctions() -> Vec<(ContractHash, Time, Time)> {
-    get_key(AUCTIONS).unwrap_or_default()
+pub fn _get_next_auction_index() -> u32 {
+    get_key(NEXT_AUCTION_INDEX).unwrap_or_revert()
 }
@@ -0,0 +1,40 @@
+//! Implementation of the `AuctionPhase` filter of the factory registry.
+use core::convert::TryFrom;
+
+use crate::structs::Time;
+
+/// Phase of a registered auction derived from its registered times.
+#[repr(u8)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AuctionPhase {
+    /// Sale not started yet.
+    Upcoming = 0,
+    /// Sale is open.
+    Live = 1,
+    /// Sale is over.
+    Ended = 2,
+}
+
+impl AuctionPhase {
+    pub fn at(auction_start_time: Time, auction_end_time: Time, time: Time) -> AuctionPhase {
+        if time.lt(&auction_start_time) {
+            AuctionPhase::Upcoming
+        } else if time.lt(&auction_end_time) {
+            AuctionPhase::Live
+        } else {
+            AuctionPhase::Ended
+        }
+    }
+}
+
+impl TryFrom<u8> for AuctionPhase {
+    type Error = ();
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(AuctionPhase::Upcoming),
+            1 => Ok(AuctionPhase::Live),
+            2 => Ok(AuctionPhase::Ended),
+            _ => Err(()),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Implementation of the `AuctionStatus` lifecycle of an auction.
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    CLType, CLTyped,
+};
+
+/// Lifecycle of an auction.
+///
+/// `Created`, `TokenDeposited`, `Finalized`, `Failed` and `Cancelled` are stored, `Live` and
+/// `Ended` are derived from the auction times once the auction token is deposited.
+#[repr(u8)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AuctionStatus {
+    /// Waiting for the auction token.
+    Created = 0,
+    /// Auction token is deposited, sale not started yet.
+    TokenDeposited = 1,
+    /// Sale is open.
+    Live = 2,
+    /// Sale is over and waiting for `finalize`.
+    Ended = 3,
+    /// Results are locked, users can claim.
+    Finalized = 4,
+    /// Sale ended below the soft cap, users can refund.
+    Failed = 5,
+    /// Sale is cancelled by admin, users can refund.
+    Cancelled = 6,
+}
+
+impl CLTyped for AuctionStatus {
+    fn cl_type() -> CLType {
+        CLType::U8
+    }
+}
+
+impl ToBytes for AuctionStatus {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        (*self as u8).to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        (*self as u8).serialized_length()
+    }
+}
+
+impl FromBytes for AuctionStatus {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (value, remainder) = u8::from_bytes(bytes)?;
+        let status = AuctionStatus::try_from(value).map_err(|_| bytesrepr::Error::Formatting)?;
+        Ok((status, remainder))
+    }
+}
+
+impl TryFrom<u8> for AuctionStatus {
+    type Error = ();
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(AuctionStatus::Created),
+            1 => Ok(AuctionStatus::TokenDeposited),
+            2 => Ok(AuctionStatus::Live),
+            3 => Ok(AuctionStatus::Ended),
+            4 => Ok(AuctionStatus::Finalized),
+            5 => Ok(AuctionStatus::Failed),
+            6 => Ok(AuctionStatus::Cancelled),
+            _ => Err(()),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Implementation of the `MerkleFormat` of the whitelist.
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    CLType, CLTyped,
+};
+
+/// Format of the whitelist merkle tree and of the proofs given to `create_order`.
+#[repr(u8)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MerkleFormat {
+    /// Text leaves and proofs with explicit sides.
+    Legacy = 0,
+    /// ABI encoded leaves and sorted-pair proofs, compatible with OpenZeppelin and merkletreejs.
+    Sorted = 1,
+}
+
+impl CLTyped for MerkleFormat {
+    fn cl_type() -> CLType {
+        CLType::U8
+    }
+}
+
+impl ToBytes for MerkleFormat {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        (*self as u8).to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        (*self as u8).serialized_length()
+    }
+}
+
+impl FromBytes for MerkleFormat {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (value, remainder) = u8::from_bytes(bytes)?;
+        let format = MerkleFormat::try_from(value).map_err(|_| bytesrepr::Error::Formatting)?;
+        Ok((format, remainder))
+    }
+}
+
+impl TryFrom<u8> for MerkleFormat {
+    type Error = ();
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(MerkleFormat::Legacy),
+            1 => Ok(MerkleFormat::Sorted),
+            _ => Err(()),
+        }
+    }
+}
@@ -1,3 +1,9 @@
 mod address;
+mod auction_phase;
+mod auction_status;
+mod merkle_format;
 
 pub use address::Address;
+pub use auction_phase::AuctionPhase;
+pub use auction_status::AuctionStatus;
+pub use merkle_format::MerkleFormat;
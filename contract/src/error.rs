@@ -34,6 +34,28 @@ pub enum Error {
     InvalidPayToken = 55,
     InvalidMerkleRoot = 56,
     AlreadySettedToken = 57,
+    AuctionTokenNotSetted = 58,
+    AlreadyWithdrawn = 59,
+    SaleNotFailed = 61,
+    NotExistRefund = 62,
+    InvalidStatus = 63,
+    NotExistClaimable = 64,
+    InvalidSchedulePercent = 65,
+    ScheduleBeforeAuctionEnd = 66,
+    InvalidAuctionTime = 67,
+    InvalidAuctionTokenPrice = 68,
+    SchedulesFrozen = 69,
+    InvalidGetter = 70,
+    InvalidFee = 71,
+    ClaimDeadlinePassed = 72,
+    InvalidClaimDeadline = 73,
+    NothingToWithdraw = 74,
+    MalformedProof = 75,
+    ProofTooLong = 76,
+    UnknownRound = 77,
+    OutOfRoundTime = 78,
+    InvalidRoundTime = 79,
+    LeafRevoked = 80,
 
     // Contract Error
     InvalidContext = 90,
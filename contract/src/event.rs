@@ -1,22 +1,272 @@
-use alloc::string::String;
-use casper_types::{account::AccountHash, ContractHash, U256};
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{account::AccountHash, ContractHash, ContractPackageHash, Key, U256};
 
-use crate::structs::{Schedules, Time};
+use crate::{
+    data::{get_events_length, set_events_length, Events},
+    enums::{AuctionStatus, MerkleFormat},
+    structs::{Schedules, Time},
+};
 
 pub enum CasperIdoEvent {
     AuctionCreated {
-        id: String,
-        info: String,
         creator: AccountHash,
         auction_created_time: Time,
         auction_start_time: Time,
         auction_end_time: Time,
-        project_open_time: Time,
-        auction_token: ContractHash,
         auction_token_price: U256,
         auction_token_capacity: U256,
         schedules: Schedules,
     },
+    StatusChanged {
+        status: AuctionStatus,
+    },
+    OrderCreated {
+        account: AccountHash,
+        amount: U256,
+    },
+    OrderCancelled {
+        account: AccountHash,
+        amount: U256,
+    },
+    Claimed {
+        account: AccountHash,
+        amount: U256,
+    },
+    Refunded {
+        account: AccountHash,
+        amount: U256,
+    },
+    FundsWithdrawn {
+        pay_token: Option<ContractHash>,
+        treasury_wallet: AccountHash,
+        amount: U256,
+    },
+    FeeCollected {
+        pay_token: Option<ContractHash>,
+        fee_wallet: AccountHash,
+        amount: U256,
+    },
+    MerkleRootChanged {
+        merkle_root: [u8; 32],
+        merkle_format: MerkleFormat,
+        grace_period: Time,
+    },
+    LeavesRevoked {
+        leaves: Vec<[u8; 32]>,
+        revoked: bool,
+    },
+    RoundSet {
+        round_id: String,
+        merkle_root: [u8; 32],
+        start_time: Time,
+        end_time: Time,
+    },
+    PriceChanged {
+        auction_token_price: U256,
+        auction_token_price_denominator: U256,
+    },
+    SchedulesChanged {
+        auction_start_time: Time,
+        auction_end_time: Time,
+        schedules: Schedules,
+    },
+    TreasuryChanged {
+        treasury_wallet: AccountHash,
+    },
+    UnsoldWithdrawn {
+        recipient: AccountHash,
+        amount: U256,
+    },
+    UnclaimedSwept {
+        recipient: AccountHash,
+        amount: U256,
+    },
+    AdminAdded {
+        admin: Key,
+    },
+    AdminRemoved {
+        admin: Key,
+    },
 }
 
-pub(crate) fn emit(_event: &CasperIdoEvent) {}
+impl CasperIdoEvent {
+    pub fn type_name(&self) -> String {
+        match self {
+            CasperIdoEvent::AuctionCreated { .. } => "auction_created",
+            CasperIdoEvent::StatusChanged { .. } => "status_changed",
+            CasperIdoEvent::OrderCreated { .. } => "order_created",
+            CasperIdoEvent::OrderCancelled { .. } => "order_cancelled",
+            CasperIdoEvent::Claimed { .. } => "claimed",
+            CasperIdoEvent::Refunded { .. } => "refunded",
+            CasperIdoEvent::FundsWithdrawn { .. } => "funds_withdrawn",
+            CasperIdoEvent::FeeCollected { .. } => "fee_collected",
+            CasperIdoEvent::MerkleRootChanged { .. } => "merkle_root_changed",
+            CasperIdoEvent::LeavesRevoked { .. } => "leaves_revoked",
+            CasperIdoEvent::RoundSet { .. } => "round_set",
+            CasperIdoEvent::PriceChanged { .. } => "price_changed",
+            CasperIdoEvent::SchedulesChanged { .. } => "schedules_changed",
+            CasperIdoEvent::TreasuryChanged { .. } => "treasury_changed",
+            CasperIdoEvent::UnsoldWithdrawn { .. } => "unsold_withdrawn",
+            CasperIdoEvent::UnclaimedSwept { .. } => "unclaimed_swept",
+            CasperIdoEvent::AdminAdded { .. } => "admin_added",
+            CasperIdoEvent::AdminRemoved { .. } => "admin_removed",
+        }
+        .to_string()
+    }
+}
+
+fn pay_token_to_string(pay_token: &Option<ContractHash>) -> String {
+    match pay_token {
+        Some(token) => token.to_formatted_string(),
+        None => "cspr".to_string(),
+    }
+}
+
+/// Append `event` to the `events` dictionary under the next sequence number.
+pub(crate) fn emit(contract_package_hash: ContractPackageHash, event: &CasperIdoEvent) {
+    let mut param = BTreeMap::new();
+    param.insert(
+        "contract_package_hash".to_string(),
+        contract_package_hash.to_formatted_string(),
+    );
+    param.insert("event_type".to_string(), event.type_name());
+    match event {
+        CasperIdoEvent::AuctionCreated {
+            creator,
+            auction_created_time,
+            auction_start_time,
+            auction_end_time,
+            auction_token_price,
+            auction_token_capacity,
+            schedules,
+        } => {
+            param.insert("creator".to_string(), creator.to_formatted_string());
+            param.insert(
+                "auction_created_time".to_string(),
+                auction_created_time.to_string(),
+            );
+            param.insert(
+                "auction_start_time".to_string(),
+                auction_start_time.to_string(),
+            );
+            param.insert("auction_end_time".to_string(), auction_end_time.to_string());
+            param.insert(
+                "auction_token_price".to_string(),
+                auction_token_price.to_string(),
+            );
+            param.insert(
+                "auction_token_capacity".to_string(),
+                auction_token_capacity.to_string(),
+            );
+            param.insert("schedules".to_string(), format!("{:?}", schedules));
+        }
+        CasperIdoEvent::StatusChanged { status } => {
+            param.insert("status".to_string(), (*status as u8).to_string());
+        }
+        CasperIdoEvent::OrderCreated { account, amount }
+        | CasperIdoEvent::OrderCancelled { account, amount }
+        | CasperIdoEvent::Claimed { account, amount }
+        | CasperIdoEvent::Refunded { account, amount } => {
+            param.insert("account".to_string(), account.to_formatted_string());
+            param.insert("amount".to_string(), amount.to_string());
+        }
+        CasperIdoEvent::FundsWithdrawn {
+            pay_token,
+            treasury_wallet,
+            amount,
+        } => {
+            param.insert("pay_token".to_string(), pay_token_to_string(pay_token));
+            param.insert(
+                "treasury_wallet".to_string(),
+                treasury_wallet.to_formatted_string(),
+            );
+            param.insert("amount".to_string(), amount.to_string());
+        }
+        CasperIdoEvent::FeeCollected {
+            pay_token,
+            fee_wallet,
+            amount,
+        } => {
+            param.insert("pay_token".to_string(), pay_token_to_string(pay_token));
+            param.insert("fee_wallet".to_string(), fee_wallet.to_formatted_string());
+            param.insert("amount".to_string(), amount.to_string());
+        }
+        CasperIdoEvent::MerkleRootChanged {
+            merkle_root,
+            merkle_format,
+            grace_period,
+        } => {
+            param.insert("merkle_root".to_string(), hex::encode(merkle_root));
+            param.insert(
+                "merkle_format".to_string(),
+                (*merkle_format as u8).to_string(),
+            );
+            param.insert("grace_period".to_string(), grace_period.to_string());
+        }
+        CasperIdoEvent::LeavesRevoked { leaves, revoked } => {
+            let leaves: Vec<String> = leaves.iter().map(hex::encode).collect();
+            param.insert("leaves".to_string(), leaves.join(","));
+            param.insert("revoked".to_string(), revoked.to_string());
+        }
+        CasperIdoEvent::RoundSet {
+            round_id,
+            merkle_root,
+            start_time,
+            end_time,
+        } => {
+            param.insert("round_id".to_string(), round_id.to_string());
+            param.insert("merkle_root".to_string(), hex::encode(merkle_root));
+            param.insert("start_time".to_string(), start_time.to_string());
+            param.insert("end_time".to_string(), end_time.to_string());
+        }
+        CasperIdoEvent::PriceChanged {
+            auction_token_price,
+            auction_token_price_denominator,
+        } => {
+            param.insert(
+                "auction_token_price".to_string(),
+                auction_token_price.to_string(),
+            );
+            param.insert(
+                "auction_token_price_denominator".to_string(),
+                auction_token_price_denominator.to_string(),
+            );
+        }
+        CasperIdoEvent::SchedulesChanged {
+            auction_start_time,
+            auction_end_time,
+            schedules,
+        } => {
+            param.insert(
+                "auction_start_time".to_string(),
+                auction_start_time.to_string(),
+            );
+            param.insert("auction_end_time".to_string(), auction_end_time.to_string());
+            param.insert("schedules".to_string(), format!("{:?}", schedules));
+        }
+        CasperIdoEvent::TreasuryChanged { treasury_wallet } => {
+            param.insert(
+                "treasury_wallet".to_string(),
+                treasury_wallet.to_formatted_string(),
+            );
+        }
+        CasperIdoEvent::UnsoldWithdrawn { recipient, amount }
+        | CasperIdoEvent::UnclaimedSwept { recipient, amount } => {
+            param.insert("recipient".to_string(), recipient.to_formatted_string());
+            param.insert("amount".to_string(), amount.to_string());
+        }
+        CasperIdoEvent::AdminAdded { admin } | CasperIdoEvent::AdminRemoved { admin } => {
+            param.insert("admin".to_string(), admin.to_formatted_string());
+        }
+    }
+
+    let index = get_events_length();
+    Events::instance().set(index, param);
+    set_events_length(index.checked_add(1).unwrap_or_revert());
+}
@@ -1,21 +1,34 @@
+use alloc::{string::String, vec::Vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
 use casper_types::{account::AccountHash, ContractHash, U256};
 use contract_utils::{ContractContext, ContractStorage};
 
 use crate::{
     data::{
-        _get_auctions, _get_fee_denominator, _get_fee_wallet, _set_auctions, _set_fee_denominator,
-        _set_fee_wallet,
+        AuctionIndexes, Auctions, _get_auctions_length, _get_fee_denominator, _get_fee_wallet,
+        _get_next_auction_index, _set_auctions_length, _set_fee_denominator, _set_fee_wallet,
+        _set_next_auction_index,
     },
-    structs::Time,
+    enums::AuctionPhase,
+    structs::{AuctionRecord, Time},
+    Error,
 };
 
 pub trait Factory<Storage: ContractStorage>: ContractContext<Storage> {
     fn init(&mut self, fee_denominator: U256, fee_wallet: AccountHash) {
         self.set_fee_denominator(fee_denominator);
         self.set_fee_wallet(fee_wallet);
+        _set_auctions_length(0);
+        _set_next_auction_index(0);
+        Auctions::init();
+        AuctionIndexes::init();
     }
 
+    /// Denominator of the sale fee numerators, zero is refused
     fn set_fee_denominator(&mut self, fee_denominator: U256) {
+        if fee_denominator.is_zero() {
+            runtime::revert(Error::InvalidFee);
+        }
         _set_fee_denominator(fee_denominator);
     }
 
@@ -32,15 +45,102 @@ pub trait Factory<Storage: ContractStorage>: ContractContext<Storage> {
         _get_fee_wallet()
     }
 
-    fn add_auction(&mut self, auction_contract: ContractHash, start_time: Time, end_time: Time) {
-        let mut auctions = _get_auctions();
-        auctions.push((auction_contract, start_time, end_time));
-        _set_auctions(auctions);
+    fn add_auction(
+        &mut self,
+        auction_contract: ContractHash,
+        start_time: Time,
+        end_time: Time,
+        creator: AccountHash,
+    ) {
+        let auction_indexes = AuctionIndexes::instance();
+        if auction_indexes.get(&auction_contract).is_some() {
+            runtime::revert(Error::AlreadyExistAuction);
+        }
+
+        let index = _get_next_auction_index();
+        Auctions::instance().set(
+            index,
+            AuctionRecord {
+                auction_contract,
+                auction_start_time: start_time,
+                auction_end_time: end_time,
+                creator,
+                registered_time: Time::from(runtime::get_blocktime()),
+                info: String::new(),
+            },
+        );
+        auction_indexes.set(&auction_contract, index);
+        _set_next_auction_index(index.checked_add(1).unwrap_or_revert());
+        _set_auctions_length(_get_auctions_length().checked_add(1).unwrap_or_revert());
+    }
+
+    /// Remove leaving the index empty, so the other auctions keep their page position
+    fn remove_auction(&mut self, auction_contract: ContractHash) {
+        let auction_indexes = AuctionIndexes::instance();
+        let index = auction_indexes
+            .get(&auction_contract)
+            .unwrap_or_revert_with(Error::NotExistAuction);
+
+        Auctions::instance().remove(index);
+        auction_indexes.remove(&auction_contract);
+        _set_auctions_length(_get_auctions_length().checked_sub(1).unwrap_or_revert());
+    }
+
+    /// Keep the phase of a registered auction in line with its rescheduled times,
+    /// auctions removed from the registry are ignored
+    fn update_auction_times(
+        &mut self,
+        auction_contract: ContractHash,
+        start_time: Time,
+        end_time: Time,
+    ) {
+        if let Some(index) = AuctionIndexes::instance().get(&auction_contract) {
+            let mut auction = Auctions::instance().get(index).unwrap_or_revert();
+            auction.auction_start_time = start_time;
+            auction.auction_end_time = end_time;
+            Auctions::instance().set(index, auction);
+        }
+    }
+
+    /// Project metadata shown by the launchpad
+    fn set_auction_info(&mut self, auction_contract: ContractHash, info: String) {
+        let index = AuctionIndexes::instance()
+            .get(&auction_contract)
+            .unwrap_or_revert_with(Error::NotExistAuction);
+        let mut auction = Auctions::instance().get(index).unwrap_or_revert();
+        auction.info = info;
+        Auctions::instance().set(index, auction);
+    }
+
+    fn get_auction(&self, auction_contract: ContractHash) -> AuctionRecord {
+        let index = AuctionIndexes::instance()
+            .get(&auction_contract)
+            .unwrap_or_revert_with(Error::NotExistAuction);
+        Auctions::instance().get(index).unwrap_or_revert()
+    }
+
+    fn get_auctions_length(&self) -> u32 {
+        _get_auctions_length()
     }
 
-    fn remove_auction(&mut self, index: usize) {
-        let mut auctions = _get_auctions();
-        auctions.remove(index);
-        _set_auctions(auctions);
+    /// Auctions in `phase` among the registry indexes `offset..offset + limit`, every auction
+    /// when `phase` is `None`. Removed or filtered out auctions leave their index out, so a page
+    /// can be shorter than `limit`; the next page starts at `offset + limit`.
+    fn get_auctions(
+        &self,
+        phase: Option<AuctionPhase>,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<AuctionRecord> {
+        let auctions = Auctions::instance();
+        let current_block_time = Time::from(runtime::get_blocktime());
+        let end = offset.saturating_add(limit).min(_get_next_auction_index());
+        (offset..end)
+            .filter_map(|index| auctions.get(index))
+            .filter(|auction| match phase {
+                Some(phase) => auction.phase(current_block_time).eq(&phase),
+                None => true,
+            })
+            .collect()
     }
 }
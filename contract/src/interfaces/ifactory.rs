@@ -15,20 +15,27 @@ impl IFactory {
     pub fn get_fee_denominator(&self) -> U256 {
         runtime::call_contract(self.contract_hash, "get_fee_denominator", runtime_args! {})
     }
-    pub fn get_treasury_wallet(&self) -> AccountHash {
-        runtime::call_contract(self.contract_hash, "get_treasury_wallet", runtime_args! {})
+    pub fn get_fee_wallet(&self) -> AccountHash {
+        runtime::call_contract(self.contract_hash, "get_fee_wallet", runtime_args! {})
     }
-    pub fn add_auction(
-        &self,
-        auction_contract: ContractHash,
-        auction_start_time: Time,
-        auction_end_time: Time,
-    ) {
+    /// Register the calling auction
+    pub fn create_auction(&self, auction_start_time: Time, auction_end_time: Time) {
         runtime::call_contract(
             self.contract_hash,
-            "add_auction",
+            "create_auction",
+            runtime_args! {
+              "auction_start_time" => auction_start_time,
+              "auction_end_time" => auction_end_time
+            },
+        )
+    }
+
+    /// Reschedule the calling auction in the registry
+    pub fn update_auction_times(&self, auction_start_time: Time, auction_end_time: Time) {
+        runtime::call_contract(
+            self.contract_hash,
+            "update_auction_times",
             runtime_args! {
-              "auction_contract" => auction_contract.to_formatted_string(),
               "auction_start_time" => auction_start_time,
               "auction_end_time" => auction_end_time
             },
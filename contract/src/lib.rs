@@ -8,7 +8,7 @@ pub mod constants;
 mod data;
 pub mod enums;
 mod error;
-mod event;
+pub mod event;
 mod factory;
 mod interfaces;
 pub mod libs;
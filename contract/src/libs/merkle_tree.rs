@@ -1,73 +1,180 @@
 #![allow(dead_code)]
 //! Implementation of merkle_tree.
+//!
+//! Whitelists are binary merkle trees hashed with keccak256 over sorted pairs, the same as
+//! OpenZeppelin `MerkleProof` and merkletreejs with `sortPairs`. Leaves are hashed twice like
+//! OpenZeppelin `StandardMerkleTree`, so an inner node can't pass as a leaf.
+//!
+//! The legacy format hashes the text `"{account_hash}_{tier:?}"` and gives the side of every
+//! proof item.
 
-use alloc::string::String;
+use alloc::{format, string::String, vec::Vec};
+use core::convert::TryFrom;
 
-use alloc::vec::Vec;
-
-use casper_contract::contract_api::runtime;
-
-use casper_types::ApiError;
+use casper_types::{account::AccountHash, U256};
 use tiny_keccak::Hasher;
 
+use crate::{constants::MAX_PROOF_LENGTH, Error};
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Position {
     Left = 0,
     Right = 1,
 }
 
-impl Position {
-    pub fn from_u8(value: u8) -> Position {
+impl TryFrom<u8> for Position {
+    type Error = Error;
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            0 => Position::Left,
-            1 => Position::Right,
-            _ => panic!("Unknown value: {}", value),
+            0 => Ok(Position::Left),
+            1 => Ok(Position::Right),
+            _ => Err(Error::MalformedProof),
         }
     }
 }
 
-/// Verify leaf is in the tree
-pub fn verify(root: String, leaf: String, proof: Vec<(String, u8)>) {
-    let converted_proof: Vec<(Vec<u8>, Position)> = proof
-        .iter()
-        .map(|proof| {
-            (
-                hex::decode(proof.0.clone()).unwrap(),
-                Position::from_u8(proof.1),
-            )
-        })
-        .collect();
-
-    let leaf_bytes: &[u8] = leaf.as_bytes();
+/// Proof of a whitelist entry in one of the supported formats.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MerkleProof {
+    /// Siblings from the leaf up, hashed as sorted pairs.
+    Sorted(Vec<[u8; 32]>),
+    /// Hex encoded siblings with their side, `0` left and `1` right.
+    Legacy(Vec<(String, u8)>),
+}
 
+fn keccak256(data: &[&[u8]]) -> [u8; 32] {
     let mut keccak = tiny_keccak::Keccak::v256();
     let mut result: [u8; 32] = Default::default();
-    keccak.update(leaf_bytes);
+    for item in data {
+        keccak.update(item);
+    }
     keccak.finalize(&mut result);
+    result
+}
 
-    let mut computed_hash: &[u8; 32] = &result;
-    let mut result: [u8; 32] = Default::default();
-    for proof_item in converted_proof {
-        match proof_item.1 {
-            Position::Right => {
-                let mut keccak = tiny_keccak::Keccak::v256();
-                keccak.update(computed_hash);
-                keccak.update(&proof_item.0);
-                keccak.finalize(&mut result);
-            }
-            Position::Left => {
-                let mut keccak = tiny_keccak::Keccak::v256();
-                keccak.update(&proof_item.0);
-                keccak.update(computed_hash);
-                keccak.finalize(&mut result);
+/// Leaf of `account` with `tier`, `keccak256(keccak256(abi.encode(account_hash, tier)))`
+pub fn leaf(account: AccountHash, tier: U256) -> [u8; 32] {
+    let mut tier_bytes = [0u8; 32];
+    tier.to_big_endian(&mut tier_bytes);
+    keccak256(&[&keccak256(&[account.as_bytes(), &tier_bytes])])
+}
+
+/// Parent of two nodes, the smaller one is hashed first
+pub fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    if a.le(b) {
+        keccak256(&[a, b])
+    } else {
+        keccak256(&[b, a])
+    }
+}
+
+/// Root rebuilt from `leaf` and its `proof`
+pub fn process_proof(leaf: [u8; 32], proof: &[[u8; 32]]) -> [u8; 32] {
+    proof.iter().fold(leaf, |computed_hash, proof_item| {
+        hash_pair(&computed_hash, proof_item)
+    })
+}
+
+/// Verify leaf is in the tree. The leaf is `keccak256(keccak256(abi.encode(bytes32 account_hash,
+/// uint256 tier)))`, the 64 byte encoding hashed twice as built by [`leaf`].
+pub fn verify(root: [u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> Result<(), Error> {
+    if proof.len() > MAX_PROOF_LENGTH {
+        return Err(Error::ProofTooLong);
+    }
+    if !process_proof(leaf, proof).eq(&root) {
+        return Err(Error::NotWhiteListed);
+    }
+    Ok(())
+}
+
+/// Verify every leaf is in the tree with a single multiproof, like OpenZeppelin
+/// `multiProofVerify`. `leaves` are in the order the multiproof consumes them and
+/// `proof_flags` tells for each hash whether its second node is a leaf or computed hash
+/// instead of the next `proof` item.
+pub fn multi_proof_verify(
+    root: [u8; 32],
+    leaves: &[[u8; 32]],
+    proof: &[[u8; 32]],
+    proof_flags: &[bool],
+) -> Result<(), Error> {
+    if leaves.len() + proof.len() != proof_flags.len() + 1 {
+        return Err(Error::MalformedProof);
+    }
+
+    let mut hashes: Vec<[u8; 32]> = Vec::with_capacity(proof_flags.len());
+    let mut leaf_pos = 0;
+    let mut hash_pos = 0;
+    let mut proof_pos = 0;
+    let mut next_node = |hashes: &Vec<[u8; 32]>| -> Result<[u8; 32], Error> {
+        if leaf_pos < leaves.len() {
+            leaf_pos += 1;
+            return Ok(leaves[leaf_pos - 1]);
+        }
+        hash_pos += 1;
+        hashes
+            .get(hash_pos - 1)
+            .copied()
+            .ok_or(Error::MalformedProof)
+    };
+    for proof_flag in proof_flags {
+        let a = next_node(&hashes)?;
+        let b = if *proof_flag {
+            next_node(&hashes)?
+        } else {
+            proof_pos += 1;
+            *proof.get(proof_pos - 1).ok_or(Error::MalformedProof)?
+        };
+        hashes.push(hash_pair(&a, &b));
+    }
+
+    let computed_root = match hashes.last() {
+        Some(computed_root) => {
+            if proof_pos != proof.len() {
+                return Err(Error::MalformedProof);
             }
+            *computed_root
         }
-        computed_hash = &result;
+        None => leaves
+            .first()
+            .or_else(|| proof.first())
+            .copied()
+            .ok_or(Error::MalformedProof)?,
+    };
+    if !computed_root.eq(&root) {
+        return Err(Error::NotWhiteListed);
+    }
+    Ok(())
+}
+
+/// Leaf of `account` with `tier` in the legacy format
+pub fn legacy_leaf(account: AccountHash, tier: U256) -> String {
+    format!("{}_{:?}", account, tier)
+}
+
+/// Verify leaf of the legacy format is in the tree
+pub fn verify_legacy(root: [u8; 32], leaf: String, proof: Vec<(String, u8)>) -> Result<(), Error> {
+    if proof.len() > MAX_PROOF_LENGTH {
+        return Err(Error::ProofTooLong);
     }
+    let converted_proof = proof
+        .iter()
+        .map(|proof| {
+            let mut node = [0u8; 32];
+            hex::decode_to_slice(&proof.0, &mut node).map_err(|_| Error::MalformedProof)?;
+            Ok((node, Position::try_from(proof.1)?))
+        })
+        .collect::<Result<Vec<([u8; 32], Position)>, Error>>()?;
 
-    let computed_root = hex::encode(*computed_hash);
+    let mut computed_hash = keccak256(&[leaf.as_bytes()]);
+    for proof_item in converted_proof {
+        computed_hash = match proof_item.1 {
+            Position::Right => keccak256(&[&computed_hash, &proof_item.0]),
+            Position::Left => keccak256(&[&proof_item.0, &computed_hash]),
+        };
+    }
 
-    if !computed_root.eq(&root) {
-        runtime::revert(ApiError::PermissionDenied);
+    if !computed_hash.eq(&root) {
+        return Err(Error::NotWhiteListed);
     }
+    Ok(())
 }
@@ -0,0 +1,103 @@
+//! Implementation of `AuctionInfo`, a snapshot of the auction returned by the `auction_info` getter.
+use alloc::vec::Vec;
+
+use casper_types::{
+    account::AccountHash,
+    bytesrepr::{self, FromBytes, ToBytes},
+    CLType, CLTyped, ContractHash, U256,
+};
+
+use super::Time;
+use crate::enums::AuctionStatus;
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct AuctionInfo {
+    pub creator: AccountHash,
+    pub auction_start_time: Time,
+    pub auction_end_time: Time,
+    pub auction_token: ContractHash,
+    /// Whole pay tokens per auction token, divided by `auction_token_price_denominator`.
+    pub auction_token_price: U256,
+    pub auction_token_price_denominator: U256,
+    pub auction_token_capacity: U256,
+    pub soft_cap: U256,
+    /// `None` when the auction is paid with CSPR.
+    pub pay_token: Option<ContractHash>,
+    pub sold_amount: U256,
+    pub total_participants: u64,
+    pub status: AuctionStatus,
+}
+
+impl CLTyped for AuctionInfo {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for AuctionInfo {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.creator.to_bytes()?);
+        buffer.extend(self.auction_start_time.to_bytes()?);
+        buffer.extend(self.auction_end_time.to_bytes()?);
+        buffer.extend(self.auction_token.to_bytes()?);
+        buffer.extend(self.auction_token_price.to_bytes()?);
+        buffer.extend(self.auction_token_price_denominator.to_bytes()?);
+        buffer.extend(self.auction_token_capacity.to_bytes()?);
+        buffer.extend(self.soft_cap.to_bytes()?);
+        buffer.extend(self.pay_token.to_bytes()?);
+        buffer.extend(self.sold_amount.to_bytes()?);
+        buffer.extend(self.total_participants.to_bytes()?);
+        buffer.extend(self.status.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.creator.serialized_length()
+            + self.auction_start_time.serialized_length()
+            + self.auction_end_time.serialized_length()
+            + self.auction_token.serialized_length()
+            + self.auction_token_price.serialized_length()
+            + self.auction_token_price_denominator.serialized_length()
+            + self.auction_token_capacity.serialized_length()
+            + self.soft_cap.serialized_length()
+            + self.pay_token.serialized_length()
+            + self.sold_amount.serialized_length()
+            + self.total_participants.serialized_length()
+            + self.status.serialized_length()
+    }
+}
+
+impl FromBytes for AuctionInfo {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (creator, remainder) = AccountHash::from_bytes(bytes)?;
+        let (auction_start_time, remainder) = Time::from_bytes(remainder)?;
+        let (auction_end_time, remainder) = Time::from_bytes(remainder)?;
+        let (auction_token, remainder) = ContractHash::from_bytes(remainder)?;
+        let (auction_token_price, remainder) = U256::from_bytes(remainder)?;
+        let (auction_token_price_denominator, remainder) = U256::from_bytes(remainder)?;
+        let (auction_token_capacity, remainder) = U256::from_bytes(remainder)?;
+        let (soft_cap, remainder) = U256::from_bytes(remainder)?;
+        let (pay_token, remainder) = Option::<ContractHash>::from_bytes(remainder)?;
+        let (sold_amount, remainder) = U256::from_bytes(remainder)?;
+        let (total_participants, remainder) = u64::from_bytes(remainder)?;
+        let (status, remainder) = AuctionStatus::from_bytes(remainder)?;
+        Ok((
+            AuctionInfo {
+                creator,
+                auction_start_time,
+                auction_end_time,
+                auction_token,
+                auction_token_price,
+                auction_token_price_denominator,
+                auction_token_capacity,
+                soft_cap,
+                pay_token,
+                sold_amount,
+                total_participants,
+                status,
+            },
+            remainder,
+        ))
+    }
+}
@@ -0,0 +1,79 @@
+//! Implementation of `AuctionRecord`, an entry of the factory registry.
+use alloc::{string::String, vec::Vec};
+
+use casper_types::{
+    account::AccountHash,
+    bytesrepr::{self, FromBytes, ToBytes},
+    CLType, CLTyped, ContractHash,
+};
+
+use super::Time;
+use crate::enums::AuctionPhase;
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct AuctionRecord {
+    pub auction_contract: ContractHash,
+    pub auction_start_time: Time,
+    pub auction_end_time: Time,
+    /// Account which deployed the auction.
+    pub creator: AccountHash,
+    pub registered_time: Time,
+    /// Free form project metadata set by factory admin, e.g. a json or an url.
+    pub info: String,
+}
+
+impl AuctionRecord {
+    pub fn phase(&self, time: Time) -> AuctionPhase {
+        AuctionPhase::at(self.auction_start_time, self.auction_end_time, time)
+    }
+}
+
+impl CLTyped for AuctionRecord {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for AuctionRecord {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.auction_contract.to_bytes()?);
+        buffer.extend(self.auction_start_time.to_bytes()?);
+        buffer.extend(self.auction_end_time.to_bytes()?);
+        buffer.extend(self.creator.to_bytes()?);
+        buffer.extend(self.registered_time.to_bytes()?);
+        buffer.extend(self.info.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.auction_contract.serialized_length()
+            + self.auction_start_time.serialized_length()
+            + self.auction_end_time.serialized_length()
+            + self.creator.serialized_length()
+            + self.registered_time.serialized_length()
+            + self.info.serialized_length()
+    }
+}
+
+impl FromBytes for AuctionRecord {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (auction_contract, remainder) = ContractHash::from_bytes(bytes)?;
+        let (auction_start_time, remainder) = Time::from_bytes(remainder)?;
+        let (auction_end_time, remainder) = Time::from_bytes(remainder)?;
+        let (creator, remainder) = AccountHash::from_bytes(remainder)?;
+        let (registered_time, remainder) = Time::from_bytes(remainder)?;
+        let (info, remainder) = String::from_bytes(remainder)?;
+        Ok((
+            AuctionRecord {
+                auction_contract,
+                auction_start_time,
+                auction_end_time,
+                creator,
+                registered_time,
+                info,
+            },
+            remainder,
+        ))
+    }
+}
@@ -0,0 +1,61 @@
+//! Implementation of `MerkleRootRecord`, a superseded whitelist root.
+use alloc::vec::Vec;
+
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    CLType, CLTyped,
+};
+
+use super::Time;
+
+/// Root replaced by `set_merkle_root`, proofs against it are accepted until `expiry_time`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct MerkleRootRecord {
+    pub merkle_root: [u8; 32],
+    pub activation_time: Time,
+    pub expiry_time: Time,
+}
+
+impl MerkleRootRecord {
+    pub fn is_live(&self, time: Time) -> bool {
+        time.lt(&self.expiry_time)
+    }
+}
+
+impl CLTyped for MerkleRootRecord {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for MerkleRootRecord {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.merkle_root.to_bytes()?);
+        buffer.extend(self.activation_time.to_bytes()?);
+        buffer.extend(self.expiry_time.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.merkle_root.serialized_length()
+            + self.activation_time.serialized_length()
+            + self.expiry_time.serialized_length()
+    }
+}
+
+impl FromBytes for MerkleRootRecord {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (merkle_root, remainder) = <[u8; 32]>::from_bytes(bytes)?;
+        let (activation_time, remainder) = Time::from_bytes(remainder)?;
+        let (expiry_time, remainder) = Time::from_bytes(remainder)?;
+        Ok((
+            MerkleRootRecord {
+                merkle_root,
+                activation_time,
+                expiry_time,
+            },
+            remainder,
+        ))
+    }
+}
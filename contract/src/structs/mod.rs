@@ -1,6 +1,18 @@
 use alloc::collections::BTreeMap;
 use casper_types::U256;
 
+mod auction_info;
+mod auction_record;
+mod merkle_root_record;
+mod round;
+mod vesting;
+
+pub use auction_info::AuctionInfo;
+pub use auction_record::AuctionRecord;
+pub use merkle_root_record::MerkleRootRecord;
+pub use round::Round;
+pub use vesting::Vesting;
+
 pub type Time = u64;
 
 pub type Schedules = BTreeMap<Time, U256>;
@@ -0,0 +1,81 @@
+//! Implementation of a whitelist `Round` of the sale.
+use alloc::vec::Vec;
+
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    CLType, CLTyped, U256,
+};
+
+use super::Time;
+
+/// Named sale round with its own whitelist and window, e.g. a guaranteed round for stakers
+/// followed by a FCFS round for the community.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Round {
+    /// Root of the sorted pair whitelist of the round.
+    pub merkle_root: [u8; 32],
+    pub start_time: Time,
+    pub end_time: Time,
+    /// When set every whitelisted account may order up to `max_order` in the round whatever
+    /// its tier (FCFS), otherwise the tier is the allocation of the account in the round.
+    pub max_order: Option<U256>,
+    /// Price and price denominator in the main pay token, `None` sells at the auction price.
+    pub price: Option<(U256, U256)>,
+}
+
+impl Round {
+    pub fn is_open(&self, time: Time) -> bool {
+        time.ge(&self.start_time) && time.lt(&self.end_time)
+    }
+
+    /// Most `account` with `tier` may order in the round.
+    pub fn allocation(&self, tier: U256) -> U256 {
+        self.max_order.unwrap_or(tier)
+    }
+}
+
+impl CLTyped for Round {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for Round {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.merkle_root.to_bytes()?);
+        buffer.extend(self.start_time.to_bytes()?);
+        buffer.extend(self.end_time.to_bytes()?);
+        buffer.extend(self.max_order.to_bytes()?);
+        buffer.extend(self.price.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.merkle_root.serialized_length()
+            + self.start_time.serialized_length()
+            + self.end_time.serialized_length()
+            + self.max_order.serialized_length()
+            + self.price.serialized_length()
+    }
+}
+
+impl FromBytes for Round {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (merkle_root, remainder) = <[u8; 32]>::from_bytes(bytes)?;
+        let (start_time, remainder) = Time::from_bytes(remainder)?;
+        let (end_time, remainder) = Time::from_bytes(remainder)?;
+        let (max_order, remainder) = Option::<U256>::from_bytes(remainder)?;
+        let (price, remainder) = Option::<(U256, U256)>::from_bytes(remainder)?;
+        Ok((
+            Round {
+                merkle_root,
+                start_time,
+                end_time,
+                max_order,
+                price,
+            },
+            remainder,
+        ))
+    }
+}
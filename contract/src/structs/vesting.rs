@@ -0,0 +1,113 @@
+//! Implementation of a linear `Vesting` with an unlock at TGE and a cliff.
+use alloc::vec::Vec;
+
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    CLType, CLTyped, U256,
+};
+
+use super::Time;
+
+/// Vesting which releases `tge_percent` at `tge_time`, then the rest linearly over
+/// `linear_duration` once `cliff_duration` has passed.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Vesting {
+    /// Time of the token generation event.
+    pub tge_time: Time,
+    /// Percent released at `tge_time`, `10000` is 100%.
+    pub tge_percent: U256,
+    /// Duration after `tge_time` before the linear release starts.
+    pub cliff_duration: Time,
+    /// Duration of the linear release.
+    pub linear_duration: Time,
+}
+
+impl Vesting {
+    /// Time at which everything is vested.
+    pub fn end_time(&self) -> Time {
+        self.tge_time
+            .checked_add(self.cliff_duration)
+            .unwrap_or_revert()
+            .checked_add(self.linear_duration)
+            .unwrap_or_revert()
+    }
+
+    /// Returns the part of `total` vested at `time`.
+    pub fn vested_amount(&self, total: U256, time: Time) -> U256 {
+        if time.lt(&self.tge_time) {
+            return U256::zero();
+        }
+
+        let tge_amount = total
+            .checked_mul(self.tge_percent)
+            .unwrap_or_revert()
+            .checked_div(U256::exp10(4))
+            .unwrap_or_revert();
+        let linear_start_time = self
+            .tge_time
+            .checked_add(self.cliff_duration)
+            .unwrap_or_revert();
+        if time.lt(&linear_start_time) {
+            return tge_amount;
+        }
+
+        let elapsed = time - linear_start_time;
+        if elapsed.ge(&self.linear_duration) {
+            return total;
+        }
+
+        let linear_amount = total.checked_sub(tge_amount).unwrap_or_revert();
+        tge_amount
+            .checked_add(
+                linear_amount
+                    .checked_mul(U256::from(elapsed))
+                    .unwrap_or_revert()
+                    .checked_div(U256::from(self.linear_duration))
+                    .unwrap_or_revert(),
+            )
+            .unwrap_or_revert()
+    }
+}
+
+impl CLTyped for Vesting {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for Vesting {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.tge_time.to_bytes()?);
+        buffer.extend(self.tge_percent.to_bytes()?);
+        buffer.extend(self.cliff_duration.to_bytes()?);
+        buffer.extend(self.linear_duration.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.tge_time.serialized_length()
+            + self.tge_percent.serialized_length()
+            + self.cliff_duration.serialized_length()
+            + self.linear_duration.serialized_length()
+    }
+}
+
+impl FromBytes for Vesting {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tge_time, remainder) = Time::from_bytes(bytes)?;
+        let (tge_percent, remainder) = U256::from_bytes(remainder)?;
+        let (cliff_duration, remainder) = Time::from_bytes(remainder)?;
+        let (linear_duration, remainder) = Time::from_bytes(remainder)?;
+        Ok((
+            Vesting {
+                tge_time,
+                tge_percent,
+                cliff_duration,
+                linear_duration,
+            },
+            remainder,
+        ))
+    }
+}
@@ -11,6 +11,7 @@ import {
   CLString,
   CLU256Type,
   CLU64Type,
+  CLU8Type,
 } from "casper-js-sdk";
 import {
   CasperContractClient,
@@ -60,6 +61,7 @@ export default class IDOClient extends CasperContractClient {
    * @param keys AsymmetricKey that will be used to install the contract.
    * @param contractName Name of the Factory contract.
    * @param treasuryWallet treasury wallet.
+   * @param factoryContract Factory the auction registers to, the installer must be its admin.
    * @param feeDenominator Specifies fee denominator.
    * @param paymentAmount The payment amount that will be used to install the contract.
    * @param wasmPath Path to the WASM file that will be installed.
@@ -73,8 +75,10 @@ export default class IDOClient extends CasperContractClient {
     auctionEndTime: number,
     auctionTokenPrice: BigNumberish,
     auctionTokenCapacity: BigNumberish,
+    softCap: BigNumberish,
     schedules: Map<number, BigNumberish>,
     treasuryWallet: string,
+    factoryContract: string,
     paymentAmount: string,
     wasmPath: string,
     contractPackageHash?: string,
@@ -96,11 +100,13 @@ export default class IDOClient extends CasperContractClient {
       auction_end_time: CLValueBuilder.u64(auctionEndTime),
       auction_token_price: CLValueBuilder.u256(auctionTokenPrice),
       auction_token_capacity: CLValueBuilder.u256(auctionTokenCapacity),
+      soft_cap: CLValueBuilder.u256(softCap),
       pay_token: payToken
         ? CLValueBuilder.option(Some(CLValueBuilder.string(payToken)))
         : CLValueBuilder.option(None, new CLStringType()),
       schedules: clMap,
       treasury_wallet: CLValueBuilder.string(treasuryWallet),
+      factory_contract: CLValueBuilder.string(factoryContract),
       contract_package_hash: contractPackageHash
         ? CLValueBuilder.option(
             Some(CLValueBuilder.string(contractPackageHash))
@@ -255,10 +261,19 @@ export default class IDOClient extends CasperContractClient {
     keys: Keys.AsymmetricKey,
     merkleRoot: string,
     paymentAmount: string,
-    ttl = DEFAULT_TTL
+    ttl = DEFAULT_TTL,
+    legacy = false,
+    gracePeriod?: number
   ) {
     const runtimeArgs = RuntimeArgs.fromMap({
-      merkle_root: CLValueBuilder.string(merkleRoot),
+      merkle_root: CLValueBuilder.byteArray(decodeBase16(merkleRoot)),
+      merkle_format: legacy
+        ? CLValueBuilder.option(Some(CLValueBuilder.u8(0)))
+        : CLValueBuilder.option(None, new CLU8Type()),
+      grace_period:
+        gracePeriod !== undefined
+          ? CLValueBuilder.option(Some(CLValueBuilder.u64(gracePeriod)))
+          : CLValueBuilder.option(None, new CLU64Type()),
     });
 
     return await this.contractCall({
@@ -270,6 +285,41 @@ export default class IDOClient extends CasperContractClient {
     });
   }
 
+  public async setRound(
+    keys: Keys.AsymmetricKey,
+    roundId: string,
+    merkleRoot: string,
+    startTime: number,
+    endTime: number,
+    paymentAmount: string,
+    maxOrder?: BigNumberish,
+    price?: BigNumberish,
+    priceDenominator?: BigNumberish,
+    ttl = DEFAULT_TTL
+  ) {
+    const optionalU256 = (value?: BigNumberish) =>
+      value !== undefined
+        ? CLValueBuilder.option(Some(CLValueBuilder.u256(value)))
+        : CLValueBuilder.option(None, new CLU256Type());
+    const runtimeArgs = RuntimeArgs.fromMap({
+      round_id: CLValueBuilder.string(roundId),
+      merkle_root: CLValueBuilder.byteArray(decodeBase16(merkleRoot)),
+      start_time: CLValueBuilder.u64(startTime),
+      end_time: CLValueBuilder.u64(endTime),
+      max_order: optionalU256(maxOrder),
+      price: optionalU256(price),
+      price_denominator: optionalU256(priceDenominator),
+    });
+
+    return await this.contractCall({
+      entryPoint: "set_round",
+      keys,
+      paymentAmount,
+      runtimeArgs,
+      ttl,
+    });
+  }
+
   public async setAuctionToken(
     keys: Keys.AsymmetricKey,
     auctionToken: string,
@@ -295,10 +345,15 @@ export default class IDOClient extends CasperContractClient {
     keys: Keys.AsymmetricKey,
     price: BigNumberish,
     paymentAmount: string,
-    ttl = DEFAULT_TTL
+    ttl = DEFAULT_TTL,
+    priceDenominator?: BigNumberish
   ) {
     const runtimeArgs = RuntimeArgs.fromMap({
       price: CLValueBuilder.u256(price),
+      price_denominator:
+        priceDenominator !== undefined
+          ? CLValueBuilder.option(Some(CLValueBuilder.u256(priceDenominator)))
+          : CLValueBuilder.option(None, new CLU256Type()),
     });
 
     return await this.contractCall({
@@ -16,6 +16,7 @@ const {
   INSTALL_PAYMENT_AMOUNT,
   IDO_CONTRACT,
   ERC20_CONTRACT,
+  FACTORY_CONTRACT_HASH,
 } = process.env;
 
 const private_key = Keys.Ed25519.parsePrivateKeyFile(
@@ -91,6 +92,8 @@ const deployIDO = async () => {
     token.decimals
   );
 
+  const softCap = "0";
+
   const schedules = new Map<number, BigNumberish>([]);
   schedulesInfo.forEach((schedule) => {
     schedules.set(schedule.time, schedule.percent * 10 ** 2);
@@ -111,8 +114,10 @@ const deployIDO = async () => {
     endTime,
     auctionTokenPrice,
     auctionTokenCapacity,
+    softCap,
     schedules,
     treasuryWallet,
+    FACTORY_CONTRACT_HASH!,
     INSTALL_PAYMENT_AMOUNT!,
     IDO_CONTRACT!,
     contractPackageHash,
@@ -79,7 +79,29 @@ function test_net_tiers(): Tier[] {
   });
 }
 
+// keccak256(keccak256(abi.encode(bytes32 accountHash, uint256 tier))), as OpenZeppelin StandardMerkleTree
+const encodeLeaf = (tier: Tier): Leaf => {
+  const amount = BigNumber.from(tier.amount).toHexString().slice(2).padStart(64, "0");
+  return keccak256(
+    keccak256(
+      Buffer.concat([Buffer.from(tier.account, "hex"), Buffer.from(amount, "hex")])
+    )
+  );
+};
+
 export const genMerkleTree = () => {
+  const tiers = test_net_tiers();
+  const leaves = tiers.map(encodeLeaf);
+  const tree = new MerkleTree(leaves, keccak256, { sortPairs: true });
+  const root = tree.getRoot() as Root;
+  const proof = tree.getHexProof(leaves[0]);
+
+  console.log(tree.toString());
+  console.log(tree.verify(proof, leaves[0], root));
+  return { root, proof };
+};
+
+export const genLegacyMerkleTree = () => {
   const tiers = test_net_tiers();
   const elements = tiers.map((tier) => `${tier.account}_${tier.amount}`);
   const leaves = elements.map(keccak256);
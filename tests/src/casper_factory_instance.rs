@@ -0,0 +1,155 @@
+use std::time::SystemTime;
+
+use casper_ido_contract::structs::{AuctionRecord, Time};
+use casper_types::{
+    account::AccountHash, bytesrepr::FromBytes, runtime_args, CLTyped, ContractHash, RuntimeArgs,
+    U256,
+};
+use test_env::{TestContract, TestEnv};
+
+pub struct CasperFactoryInstance(TestContract);
+
+impl CasperFactoryInstance {
+    pub fn new(
+        env: &TestEnv,
+        contract_name: &str,
+        sender: AccountHash,
+        fee_denominator: U256,
+        fee_wallet: String,
+    ) -> CasperFactoryInstance {
+        CasperFactoryInstance(TestContract::new(
+            env,
+            "casper_factory_contract.wasm",
+            contract_name,
+            sender,
+            runtime_args! {
+                "fee_denominator" => fee_denominator,
+                "fee_wallet" => fee_wallet
+            },
+        ))
+    }
+
+    pub fn contract_hash(&self) -> ContractHash {
+        self.0.contract_hash()
+    }
+
+    pub fn add_auction(
+        &self,
+        sender: AccountHash,
+        auction_contract: String,
+        auction_start_time: Time,
+        auction_end_time: Time,
+        success: bool,
+    ) {
+        self.0.call_contract_with_condition(
+            sender,
+            "add_auction",
+            runtime_args! {
+                "auction_contract" => auction_contract,
+                "auction_start_time" => auction_start_time,
+                "auction_end_time" => auction_end_time
+            },
+            success,
+        );
+    }
+
+    pub fn remove_auction(&self, sender: AccountHash, auction_contract: String) {
+        self.0.call_contract(
+            sender,
+            "remove_auction",
+            runtime_args! {
+                "auction_contract" => auction_contract
+            },
+        );
+    }
+
+    pub fn set_auction_info(&self, sender: AccountHash, auction_contract: String, info: String) {
+        self.0.call_contract(
+            sender,
+            "set_auction_info",
+            runtime_args! {
+                "auction_contract" => auction_contract,
+                "info" => info
+            },
+        );
+    }
+
+    pub fn set_fee_wallet(&self, sender: AccountHash, fee_wallet: String) {
+        self.0.call_contract(
+            sender,
+            "set_fee_wallet",
+            runtime_args! {
+                "fee_wallet" => fee_wallet
+            },
+        );
+    }
+
+    pub fn set_fee_denominator(&self, sender: AccountHash, fee_denominator: U256, success: bool) {
+        self.0.call_contract_with_condition(
+            sender,
+            "set_fee_denominator",
+            runtime_args! {
+                "fee_denominator" => fee_denominator
+            },
+            success,
+        );
+    }
+
+    pub fn get_auction(&self, sender: AccountHash, auction_contract: String) -> AuctionRecord {
+        self.query(
+            sender,
+            "get_auction",
+            runtime_args! {
+                "auction_contract" => auction_contract
+            },
+            SystemTime::now(),
+        )
+    }
+
+    pub fn get_auctions(
+        &self,
+        sender: AccountHash,
+        phase: Option<u8>,
+        offset: u32,
+        limit: u32,
+        time: SystemTime,
+    ) -> Vec<AuctionRecord> {
+        self.query(
+            sender,
+            "get_auctions",
+            runtime_args! {
+                "phase" => phase,
+                "offset" => offset,
+                "limit" => limit
+            },
+            time,
+        )
+    }
+
+    pub fn auctions_length(&self) -> u32 {
+        self.0.query_named_key("auctions_length".to_string())
+    }
+
+    pub fn fee_denominator(&self) -> U256 {
+        self.0.query_named_key("fee_denominator".to_string())
+    }
+
+    pub fn fee_wallet(&self) -> AccountHash {
+        self.0.query_named_key("fee_wallet".to_string())
+    }
+
+    /// Call a getter through the query session and read the stored `result`
+    fn query<T: CLTyped + FromBytes>(
+        &self,
+        sender: AccountHash,
+        entry_point: &str,
+        mut args: RuntimeArgs,
+        time: SystemTime,
+    ) -> T {
+        args.insert("contract_hash", self.contract_hash().to_formatted_string())
+            .unwrap();
+        args.insert("entry_point", entry_point.to_string()).unwrap();
+        self.0
+            .query_with_session(sender, "casper_ido_query.wasm", args, time)
+    }
+}
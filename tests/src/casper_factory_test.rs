@@ -0,0 +1,268 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use casper_ido_contract::structs::Schedules;
+use casper_types::{account::AccountHash, ContractHash, U256};
+use test_env::TestEnv;
+
+use crate::{
+    casper_factory_instance::CasperFactoryInstance, casper_ido_instance::CasperIdoInstance,
+};
+
+fn deploy() -> (TestEnv, CasperFactoryInstance, AccountHash) {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let factory = CasperFactoryInstance::new(
+        &env,
+        "casper_factory",
+        owner,
+        U256::exp10(4),
+        AccountHash::new([7u8; 32]).to_formatted_string(),
+    );
+    (env, factory, owner)
+}
+
+#[test]
+fn test_deploy() {
+    let (_, factory, _) = deploy();
+    assert_eq!(factory.fee_denominator(), U256::exp10(4));
+    assert_eq!(factory.fee_wallet(), AccountHash::new([7u8; 32]));
+    assert_eq!(factory.auctions_length(), 0);
+}
+
+#[test]
+fn should_refuse_zero_fee_denominator() {
+    let (_, factory, owner) = deploy();
+    factory.set_fee_denominator(owner, U256::zero(), false);
+    factory.set_fee_denominator(owner, U256::exp10(2), true);
+    assert_eq!(factory.fee_denominator(), U256::exp10(2));
+}
+
+/// Registered contracts in registry order
+fn auction_contracts(
+    factory: &CasperFactoryInstance,
+    sender: AccountHash,
+    phase: Option<u8>,
+    offset: u32,
+    limit: u32,
+) -> Vec<ContractHash> {
+    factory
+        .get_auctions(sender, phase, offset, limit, SystemTime::now())
+        .iter()
+        .map(|auction| auction.auction_contract)
+        .collect()
+}
+
+#[test]
+fn should_add_and_remove_auctions() {
+    let (_, factory, owner) = deploy();
+    let first = ContractHash::new([1u8; 32]);
+    let second = ContractHash::new([2u8; 32]);
+    let third = ContractHash::new([3u8; 32]);
+    factory.add_auction(owner, first.to_formatted_string(), 100, 200, true);
+    factory.add_auction(owner, second.to_formatted_string(), 300, 400, true);
+    factory.add_auction(owner, third.to_formatted_string(), 500, 600, true);
+    // Same auction can't be registered twice
+    factory.add_auction(owner, first.to_formatted_string(), 100, 200, false);
+    assert_eq!(factory.auctions_length(), 3);
+    assert_eq!(
+        auction_contracts(&factory, owner, None, 0, 10),
+        vec![first, second, third]
+    );
+
+    // The other auctions keep their index, so pages don't shift
+    factory.remove_auction(owner, first.to_formatted_string());
+    assert_eq!(factory.auctions_length(), 2);
+    assert_eq!(
+        auction_contracts(&factory, owner, None, 0, 10),
+        vec![second, third]
+    );
+    assert_eq!(auction_contracts(&factory, owner, None, 2, 1), vec![third]);
+
+    // A removed auction can register again at the end
+    factory.add_auction(owner, first.to_formatted_string(), 100, 200, true);
+    assert_eq!(factory.auctions_length(), 3);
+    assert_eq!(
+        auction_contracts(&factory, owner, None, 0, 10),
+        vec![second, third, first]
+    );
+
+    // Paging across the removed index, the first page is short
+    assert_eq!(auction_contracts(&factory, owner, None, 0, 2), vec![second]);
+    assert_eq!(
+        auction_contracts(&factory, owner, None, 2, 2),
+        vec![third, first]
+    );
+    assert!(auction_contracts(&factory, owner, None, 4, 2).is_empty());
+
+    let auction = factory.get_auction(owner, second.to_formatted_string());
+    assert_eq!(auction.auction_start_time, 300);
+    assert_eq!(auction.auction_end_time, 400);
+    assert_eq!(auction.creator, owner);
+}
+
+#[test]
+fn should_page_and_filter_auctions() {
+    let (_, factory, owner) = deploy();
+    let now: u64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs();
+    let ended = ContractHash::new([1u8; 32]);
+    let live = ContractHash::new([2u8; 32]);
+    let upcoming = ContractHash::new([3u8; 32]);
+    let other_upcoming = ContractHash::new([4u8; 32]);
+    factory.add_auction(
+        owner,
+        ended.to_formatted_string(),
+        now - 2000,
+        now - 1000,
+        true,
+    );
+    factory.add_auction(
+        owner,
+        live.to_formatted_string(),
+        now - 1000,
+        now + 1000,
+        true,
+    );
+    factory.add_auction(
+        owner,
+        upcoming.to_formatted_string(),
+        now + 1000,
+        now + 2000,
+        true,
+    );
+    factory.add_auction(
+        owner,
+        other_upcoming.to_formatted_string(),
+        now + 3000,
+        now + 4000,
+        true,
+    );
+
+    assert_eq!(
+        auction_contracts(&factory, owner, Some(0), 0, 10),
+        vec![upcoming, other_upcoming]
+    );
+    assert_eq!(
+        auction_contracts(&factory, owner, Some(1), 0, 10),
+        vec![live]
+    );
+    assert_eq!(
+        auction_contracts(&factory, owner, Some(2), 0, 10),
+        vec![ended]
+    );
+    assert_eq!(
+        auction_contracts(&factory, owner, None, 1, 2),
+        vec![live, upcoming]
+    );
+    // Pages are index windows, filtered auctions leave the page short
+    assert!(auction_contracts(&factory, owner, Some(0), 0, 2).is_empty());
+    assert_eq!(
+        auction_contracts(&factory, owner, Some(0), 3, 1),
+        vec![other_upcoming]
+    );
+}
+
+#[test]
+fn should_set_auction_info() {
+    let (_, factory, owner) = deploy();
+    let auction_contract = ContractHash::new([1u8; 32]).to_formatted_string();
+    factory.add_auction(owner, auction_contract.clone(), 100, 200, true);
+    factory.set_auction_info(
+        owner,
+        auction_contract.clone(),
+        "https://example.com/project.json".to_string(),
+    );
+    assert_eq!(
+        factory.get_auction(owner, auction_contract).info,
+        "https://example.com/project.json"
+    );
+}
+
+#[test]
+fn should_not_add_auction_by_non_admin() {
+    let (env, factory, _) = deploy();
+    let user = env.next_user();
+    factory.add_auction(
+        user,
+        ContractHash::new([1u8; 32]).to_formatted_string(),
+        100,
+        200,
+        false,
+    );
+}
+
+#[test]
+fn should_set_fee_wallet() {
+    let (_, factory, owner) = deploy();
+    let fee_wallet = AccountHash::new([8u8; 32]);
+    factory.set_fee_wallet(owner, fee_wallet.to_formatted_string());
+    assert_eq!(factory.fee_wallet(), fee_wallet);
+}
+
+#[test]
+fn should_register_auction_on_install() {
+    let (env, factory, owner) = deploy();
+    let mut schedules = Schedules::new();
+    schedules.insert(3000, U256::exp10(4));
+    let casper_ido_instance = CasperIdoInstance::new(
+        &env,
+        "casper_ido",
+        owner,
+        1000,
+        2000,
+        U256::exp10(9),
+        U256::exp10(12),
+        U256::zero(),
+        None,
+        schedules,
+        AccountHash::new([3u8; 32]).to_formatted_string(),
+        factory.contract_hash().to_formatted_string(),
+    );
+    assert_eq!(
+        auction_contracts(&factory, owner, None, 0, 10),
+        vec![casper_ido_instance.contract_hash()]
+    );
+}
+
+#[test]
+fn should_follow_rescheduled_auction_phase() {
+    let (env, factory, owner) = deploy();
+    let now: u64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs();
+    let mut schedules = Schedules::new();
+    schedules.insert(now + 3000, U256::exp10(4));
+    let casper_ido_instance = CasperIdoInstance::new(
+        &env,
+        "casper_ido",
+        owner,
+        now + 1000,
+        now + 2000,
+        U256::exp10(9),
+        U256::exp10(12),
+        U256::zero(),
+        None,
+        schedules.clone(),
+        AccountHash::new([3u8; 32]).to_formatted_string(),
+        factory.contract_hash().to_formatted_string(),
+    );
+    let auction_contract = casper_ido_instance.contract_hash();
+    assert_eq!(
+        auction_contracts(&factory, owner, Some(0), 0, 10),
+        vec![auction_contract]
+    );
+
+    // Starting the sale early moves it to the live auctions
+    casper_ido_instance.change_time_schedules(owner, now - 1000, now + 2000, schedules, true);
+    let auction = factory.get_auction(owner, auction_contract.to_formatted_string());
+    assert_eq!(auction.auction_start_time, now - 1000);
+    assert_eq!(auction.auction_end_time, now + 2000);
+    assert!(auction_contracts(&factory, owner, Some(0), 0, 10).is_empty());
+    assert_eq!(
+        auction_contracts(&factory, owner, Some(1), 0, 10),
+        vec![auction_contract]
+    );
+}
@@ -2,12 +2,15 @@ use std::time::SystemTime;
 
 use alloc::collections::BTreeMap;
 
-use casper_ido_contract::structs::{Schedules, Time};
+use casper_ido_contract::{
+    enums::AuctionStatus,
+    structs::{AuctionInfo, Schedules, Time},
+};
 use casper_types::{
-    account::AccountHash, bytesrepr::FromBytes, runtime_args, CLTyped, ContractHash,
-    ContractPackageHash, Key, RuntimeArgs, U256,
+    account::AccountHash,
+    bytesrepr::{FromBytes, ToBytes},
+    runtime_args, ApiError, CLTyped, ContractHash, ContractPackageHash, RuntimeArgs, U256,
 };
-use contract_utils::key_to_str;
 use test_env::{TestContract, TestEnv};
 
 pub struct CasperIdoInstance(TestContract);
@@ -21,9 +24,11 @@ impl CasperIdoInstance {
         auction_end_time: Time,
         auction_token_price: U256,
         auction_token_capacity: U256,
+        soft_cap: U256,
         pay_token: Option<String>,
         schedules: Schedules,
         treasury_wallet: String,
+        factory_contract: String,
     ) -> CasperIdoInstance {
         let exist_version: Option<String> = None;
         CasperIdoInstance(TestContract::new(
@@ -36,9 +41,11 @@ impl CasperIdoInstance {
                 "auction_end_time" => auction_end_time,
                 "auction_token_price" => auction_token_price,
                 "auction_token_capacity" => auction_token_capacity,
+                "soft_cap" => soft_cap,
                 "pay_token" => pay_token,
                 "schedules" => schedules,
                 "treasury_wallet" => treasury_wallet,
+                "factory_contract" => factory_contract,
                 "contract_package_hash" => exist_version
 
             },
@@ -82,23 +89,102 @@ impl CasperIdoInstance {
         );
     }
 
+    pub fn add_orders_with_condition(
+        &self,
+        sender: AccountHash,
+        orders: BTreeMap<String, U256>,
+        success: bool,
+    ) {
+        self.0.call_contract_with_condition(
+            sender,
+            "add_orders",
+            runtime_args! {
+                "orders" => orders
+            },
+            success,
+        );
+    }
+
+    pub fn add_verified_orders(
+        &self,
+        sender: AccountHash,
+        orders: Vec<(String, U256, U256)>,
+        proof: Vec<[u8; 32]>,
+        proof_flags: Vec<bool>,
+    ) {
+        self.0.call_contract(
+            sender,
+            "add_verified_orders",
+            runtime_args! {
+                "orders" => orders,
+                "proof" => proof,
+                "proof_flags" => proof_flags
+            },
+        );
+    }
+
+    pub fn register_whitelist(
+        &self,
+        sender: AccountHash,
+        accounts: Vec<(String, U256)>,
+        proof: Vec<[u8; 32]>,
+        proof_flags: Vec<bool>,
+    ) {
+        self.0.call_contract(
+            sender,
+            "register_whitelist",
+            runtime_args! {
+                "accounts" => accounts,
+                "proof" => proof,
+                "proof_flags" => proof_flags
+            },
+        );
+    }
+
+    pub fn set_partial_fill(&self, sender: AccountHash, partial_fill: bool, time: SystemTime) {
+        self.0.call_contract_with_time(
+            sender,
+            "set_partial_fill",
+            runtime_args! {
+                "partial_fill" => partial_fill
+            },
+            time,
+        );
+    }
+
+    pub fn set_oversubscription(
+        &self,
+        sender: AccountHash,
+        oversubscription: bool,
+        time: SystemTime,
+    ) {
+        self.0.call_contract_with_time(
+            sender,
+            "set_oversubscription",
+            runtime_args! {
+                "oversubscription" => oversubscription
+            },
+            time,
+        );
+    }
+
     pub fn change_time_schedules(
-        self,
+        &self,
         sender: AccountHash,
         auction_start_time: Time,
         auction_end_time: Time,
-        launch_time: Time,
         schedules: Schedules,
+        success: bool,
     ) {
-        self.0.call_contract(
+        self.0.call_contract_with_condition(
             sender,
-            "add_orders",
+            "change_time_schedules",
             runtime_args! {
                 "auction_start_time" => auction_start_time,
                 "auction_end_time" => auction_end_time,
-                "launch_time" => launch_time,
                 "schedules" => schedules,
             },
+            success,
         );
     }
 
@@ -112,21 +198,194 @@ impl CasperIdoInstance {
         );
     }
 
-    pub fn set_merkle_root(&self, sender: AccountHash, merkle_root: String) {
+    pub fn set_vesting(
+        &self,
+        sender: AccountHash,
+        tge_time: Time,
+        tge_percent: U256,
+        cliff_duration: Time,
+        linear_duration: Time,
+    ) {
+        self.0.call_contract(
+            sender,
+            "set_vesting",
+            runtime_args! {
+                "tge_time" => tge_time,
+                "tge_percent" => tge_percent,
+                "cliff_duration" => cliff_duration,
+                "linear_duration" => linear_duration,
+            },
+        );
+    }
+
+    pub fn set_fee_numerator(&self, sender: AccountHash, fee_numerator: U256, success: bool) {
+        self.0.call_contract_with_condition(
+            sender,
+            "set_fee_numerator",
+            runtime_args! {
+                "fee_numerator" => fee_numerator
+            },
+            success,
+        );
+    }
+
+    /// `pay_token` is `None` for CSPR
+    pub fn fee_amount(&self, pay_token: Option<ContractHash>) -> U256 {
+        let pay_token_key = match pay_token {
+            Some(token) => hex::encode(token.value()),
+            None => "cspr".to_string(),
+        };
+        self.0
+            .query_dictionary("fee_amounts", pay_token_key)
+            .unwrap_or_default()
+    }
+
+    pub fn add_pay_token(
+        &self,
+        sender: AccountHash,
+        pay_token: Option<String>,
+        price: U256,
+        price_denominator: Option<U256>,
+    ) {
+        self.0.call_contract(
+            sender,
+            "add_pay_token",
+            runtime_args! {
+                "pay_token" => pay_token,
+                "price" => price,
+                "price_denominator" => price_denominator
+            },
+        );
+    }
+
+    pub fn change_auction_token_price(
+        &self,
+        sender: AccountHash,
+        price: U256,
+        price_denominator: Option<U256>,
+    ) {
+        self.0.call_contract(
+            sender,
+            "change_auction_token_price",
+            runtime_args! {
+                "price" => price,
+                "price_denominator" => price_denominator
+            },
+        );
+    }
+
+    /// `merkle_format` is `Some(0)` for the legacy format, `None` for sorted pairs
+    pub fn set_merkle_root(
+        &self,
+        sender: AccountHash,
+        merkle_root: [u8; 32],
+        merkle_format: Option<u8>,
+    ) {
         self.0.call_contract(
             sender,
             "set_merkle_root",
             runtime_args! {
-                "merkle_root" => merkle_root
+                "merkle_root" => merkle_root,
+                "merkle_format" => merkle_format,
+                "grace_period" => Option::<u64>::None
+            },
+        );
+    }
+
+    /// Replace the sorted pair root, proofs against the old one stay valid for `grace_period`
+    pub fn set_merkle_root_with_grace_period(
+        &self,
+        sender: AccountHash,
+        merkle_root: [u8; 32],
+        grace_period: u64,
+    ) {
+        self.0.call_contract(
+            sender,
+            "set_merkle_root",
+            runtime_args! {
+                "merkle_root" => merkle_root,
+                "merkle_format" => Option::<u8>::None,
+                "grace_period" => Some(grace_period)
+            },
+        );
+    }
+
+    pub fn revoke_leaves(&self, sender: AccountHash, leaves: Vec<[u8; 32]>, revoked: bool) {
+        self.0.call_contract(
+            sender,
+            "revoke_leaves",
+            runtime_args! {
+                "leaves" => leaves,
+                "revoked" => revoked
             },
         );
     }
 
-    pub fn create_order(
+    /// `proof` is `Vec<[u8; 32]>`, or `Vec<(String, u8)>` for a legacy whitelist
+    pub fn create_order<P: CLTyped + ToBytes>(
+        &self,
+        sender: AccountHash,
+        tier: U256,
+        proof: P,
+        amount: U256,
+        time: SystemTime,
+    ) {
+        self.create_order_with_pay_token(sender, tier, proof, amount, None, time);
+    }
+
+    /// `pay_token` is `None` for the main pay token
+    pub fn create_order_with_pay_token<P: CLTyped + ToBytes>(
+        &self,
+        sender: AccountHash,
+        tier: U256,
+        proof: P,
+        amount: U256,
+        pay_token: Option<String>,
+        time: SystemTime,
+    ) {
+        self.0.call_contract_with_time(
+            sender,
+            "create_order",
+            runtime_args! {
+                "tier" => tier,
+                "proof" => proof,
+                "amount" => amount,
+                "pay_token" => pay_token,
+                "round_id" => Option::<String>::None
+            },
+            time,
+        );
+    }
+
+    /// Create an order which must revert and return its error
+    pub fn create_order_with_error<P: CLTyped + ToBytes>(
+        &self,
+        sender: AccountHash,
+        tier: U256,
+        proof: P,
+        amount: U256,
+        time: SystemTime,
+    ) -> ApiError {
+        self.0.call_contract_with_time_and_error(
+            sender,
+            "create_order",
+            runtime_args! {
+                "tier" => tier,
+                "proof" => proof,
+                "amount" => amount,
+                "pay_token" => Option::<String>::None,
+                "round_id" => Option::<String>::None
+            },
+            time,
+        )
+    }
+
+    pub fn create_order_in_round(
         &self,
         sender: AccountHash,
+        round_id: &str,
         tier: U256,
-        proof: Vec<(String, u8)>,
+        proof: Vec<[u8; 32]>,
         amount: U256,
         time: SystemTime,
     ) {
@@ -136,15 +395,105 @@ impl CasperIdoInstance {
             runtime_args! {
                 "tier" => tier,
                 "proof" => proof,
-                "amount" => amount
+                "amount" => amount,
+                "pay_token" => Option::<String>::None,
+                "round_id" => Some(round_id.to_string())
+            },
+            time,
+        );
+    }
+
+    /// Create an order in a round which must revert and return its error
+    pub fn create_order_in_round_with_error(
+        &self,
+        sender: AccountHash,
+        round_id: &str,
+        tier: U256,
+        proof: Vec<[u8; 32]>,
+        amount: U256,
+        time: SystemTime,
+    ) -> ApiError {
+        self.0.call_contract_with_time_and_error(
+            sender,
+            "create_order",
+            runtime_args! {
+                "tier" => tier,
+                "proof" => proof,
+                "amount" => amount,
+                "pay_token" => Option::<String>::None,
+                "round_id" => Some(round_id.to_string())
             },
             time,
+        )
+    }
+
+    /// `price_denominator` defaults to `10^9` when `price` is set
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_round(
+        &self,
+        sender: AccountHash,
+        round_id: &str,
+        merkle_root: [u8; 32],
+        start_time: Time,
+        end_time: Time,
+        max_order: Option<U256>,
+        price: Option<U256>,
+        price_denominator: Option<U256>,
+    ) {
+        self.0.call_contract(
+            sender,
+            "set_round",
+            runtime_args! {
+                "round_id" => round_id.to_string(),
+                "merkle_root" => merkle_root,
+                "start_time" => start_time,
+                "end_time" => end_time,
+                "max_order" => max_order,
+                "price" => price,
+                "price_denominator" => price_denominator
+            },
         );
     }
 
-    pub fn cancel_order(&self, sender: AccountHash) {
+    pub fn round_order(
+        &self,
+        sender: AccountHash,
+        account: AccountHash,
+        round_id: &str,
+        time: SystemTime,
+    ) -> U256 {
+        self.query(
+            sender,
+            "round_order",
+            runtime_args! {
+                "account" => account.to_formatted_string(),
+                "round_id" => round_id.to_string()
+            },
+            time,
+        )
+    }
+
+    pub fn cancel_order(&self, sender: AccountHash, time: SystemTime) {
         self.0
-            .call_contract(sender, "cancel_order", runtime_args! {})
+            .call_contract_with_time(sender, "cancel_order", runtime_args! {}, time)
+    }
+
+    pub fn claim_with_condition(
+        &self,
+        sender: AccountHash,
+        schedule_time: u64,
+        time: SystemTime,
+        success: bool,
+    ) {
+        self.0.call_contract_with_time_and_condition(
+            sender,
+            "claim",
+            runtime_args! {
+                "schedule_time" => schedule_time
+            },
+            time,
+            success,
+        );
     }
 
     pub fn claim(&self, sender: AccountHash, schedule_time: u64, time: SystemTime) {
@@ -158,18 +507,189 @@ impl CasperIdoInstance {
         )
     }
 
+    pub fn finalize(&self, sender: AccountHash, time: SystemTime) {
+        self.0
+            .call_contract_with_time(sender, "finalize", runtime_args! {}, time)
+    }
+
+    pub fn cancel_auction(&self, sender: AccountHash, time: SystemTime) {
+        self.0
+            .call_contract_with_time(sender, "cancel_auction", runtime_args! {}, time)
+    }
+
+    /// Stored status, `Live` and `Ended` are only derived on chain
+    pub fn status(&self) -> AuctionStatus {
+        self.0.query_named_key("status".to_string())
+    }
+
+    pub fn withdraw_funds(&self, sender: AccountHash, time: SystemTime) {
+        self.0
+            .call_contract_with_time(sender, "withdraw_funds", runtime_args! {}, time)
+    }
+
+    pub fn withdraw_unsold(&self, sender: AccountHash, time: SystemTime) {
+        self.0
+            .call_contract_with_time(sender, "withdraw_unsold", runtime_args! {}, time)
+    }
+
+    pub fn set_claim_deadline(
+        &self,
+        sender: AccountHash,
+        claim_deadline: u64,
+        sweep_wallet: Option<String>,
+    ) {
+        self.0.call_contract(
+            sender,
+            "set_claim_deadline",
+            runtime_args! {
+                "claim_deadline" => claim_deadline,
+                "sweep_wallet" => sweep_wallet
+            },
+        );
+    }
+
+    pub fn sweep_unclaimed(&self, sender: AccountHash, time: SystemTime) {
+        self.0
+            .call_contract_with_time(sender, "sweep_unclaimed", runtime_args! {}, time)
+    }
+
+    pub fn claim_refund(&self, sender: AccountHash, time: SystemTime) {
+        self.0
+            .call_contract_with_time(sender, "claim_refund", runtime_args! {}, time)
+    }
+
+    pub fn refund(&self, sender: AccountHash, time: SystemTime) {
+        self.0
+            .call_contract_with_time(sender, "refund", runtime_args! {}, time)
+    }
+
+    pub fn withdraw_auction_token(&self, sender: AccountHash, time: SystemTime) {
+        self.0
+            .call_contract_with_time(sender, "withdraw_auction_token", runtime_args! {}, time)
+    }
+
+    pub fn claim_all(&self, sender: AccountHash, time: SystemTime) {
+        self.0
+            .call_contract_with_time(sender, "claim_all", runtime_args! {}, time)
+    }
+
     pub fn schedules(&self) -> Schedules {
         self.0.query_named_key("schedules".to_string())
     }
 
-    /// Actually not working????
-    pub fn get_order(&self, sender: AccountHash) -> U256 {
-        self.0
-            .query_dictionary("orders", key_to_str(&Key::from(sender)))
-            .unwrap_or_default()
+    pub fn events_length(&self) -> u32 {
+        self.0.query_named_key("events_length".to_string())
+    }
+
+    pub fn event(&self, index: u32) -> Option<BTreeMap<String, String>> {
+        self.0.query_dictionary("events", index.to_string())
+    }
+
+    pub fn get_order(&self, sender: AccountHash, account: AccountHash, time: SystemTime) -> U256 {
+        self.query(
+            sender,
+            "get_order",
+            runtime_args! {
+                "account" => account.to_formatted_string()
+            },
+            time,
+        )
+    }
+
+    pub fn get_claimed(
+        &self,
+        sender: AccountHash,
+        account: AccountHash,
+        schedule_time: Time,
+        time: SystemTime,
+    ) -> bool {
+        self.query(
+            sender,
+            "get_claimed",
+            runtime_args! {
+                "account" => account.to_formatted_string(),
+                "schedule_time" => schedule_time
+            },
+            time,
+        )
+    }
+
+    pub fn claimable(&self, sender: AccountHash, account: AccountHash, time: SystemTime) -> U256 {
+        self.query(
+            sender,
+            "claimable",
+            runtime_args! {
+                "account" => account.to_formatted_string()
+            },
+            time,
+        )
+    }
+
+    pub fn entitlement(&self, sender: AccountHash, account: AccountHash, time: SystemTime) -> U256 {
+        self.query(
+            sender,
+            "entitlement",
+            runtime_args! {
+                "account" => account.to_formatted_string()
+            },
+            time,
+        )
+    }
+
+    pub fn claimed_amount(
+        &self,
+        sender: AccountHash,
+        account: AccountHash,
+        time: SystemTime,
+    ) -> U256 {
+        self.query(
+            sender,
+            "claimed_amount",
+            runtime_args! {
+                "account" => account.to_formatted_string()
+            },
+            time,
+        )
     }
 
-    pub fn result<T: CLTyped + FromBytes>(&self) -> T {
-        self.0.query_named_key("result".to_string())
+    pub fn total_claimed(&self) -> U256 {
+        self.0.query_named_key("total_claimed".to_string())
+    }
+
+    pub fn auction_info(&self, sender: AccountHash, time: SystemTime) -> AuctionInfo {
+        self.query(sender, "auction_info", runtime_args! {}, time)
+    }
+
+    pub fn sold_amount(&self, sender: AccountHash, time: SystemTime) -> U256 {
+        self.query(sender, "sold_amount", runtime_args! {}, time)
+    }
+
+    pub fn raised_amount(
+        &self,
+        sender: AccountHash,
+        pay_token: Option<String>,
+        time: SystemTime,
+    ) -> U256 {
+        self.query(
+            sender,
+            "get_raised_amount",
+            runtime_args! { "pay_token" => pay_token },
+            time,
+        )
+    }
+
+    /// Call a getter through the query session and read the stored `result`
+    fn query<T: CLTyped + FromBytes>(
+        &self,
+        sender: AccountHash,
+        entry_point: &str,
+        mut args: RuntimeArgs,
+        time: SystemTime,
+    ) -> T {
+        args.insert("contract_hash", self.contract_hash().to_formatted_string())
+            .unwrap();
+        args.insert("entry_point", entry_point.to_string()).unwrap();
+        self.0
+            .query_with_session(sender, "casper_ido_query.wasm", args, time)
     }
 }
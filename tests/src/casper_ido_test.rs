@@ -6,15 +6,20 @@ use std::{
 use alloc::collections::BTreeMap;
 
 use casper_ido_contract::{
-    enums::Address,
+    enums::{Address, AuctionStatus},
+    libs::merkle_tree,
     structs::{Schedules, Time},
+    Error,
 };
 use casper_types::{
-    account::AccountHash, runtime_args, PublicKey, RuntimeArgs, SecretKey, U256, U512,
+    account::AccountHash, runtime_args, ApiError, PublicKey, RuntimeArgs, SecretKey, U256, U512,
 };
 use test_env::{utils::DeploySource, TestEnv};
 
-use crate::{casper_ido_instance::CasperIdoInstance, erc20_instance::ERC20Instance};
+use crate::{
+    casper_factory_instance::CasperFactoryInstance, casper_ido_instance::CasperIdoInstance,
+    erc20_instance::ERC20Instance,
+};
 
 const PRE_CREATE_ORDER_WASM: &str = "pre_create_order.wasm";
 
@@ -50,6 +55,7 @@ fn deploy() -> (TestEnv, TestContext, AccountHash) {
     schedules.insert(since_the_epoch + 777777, U256::from(6000));
     let pay_token: Option<String> = None;
     let treasury_wallet = AccountHash::new([3u8; 32]).to_formatted_string();
+    let factory = deploy_factory(&env, owner);
     let casper_ido_instance = CasperIdoInstance::new(
         &env,
         "casper_ido",
@@ -58,9 +64,11 @@ fn deploy() -> (TestEnv, TestContext, AccountHash) {
         auction_end_time,
         auction_token_price,
         auction_token_capacity,
+        U256::zero(),
         pay_token,
         schedules,
         treasury_wallet,
+        factory.contract_hash().to_formatted_string(),
     );
 
     let test_context = TestContext {
@@ -82,6 +90,17 @@ fn _whitlisted_users() -> Vec<AccountHash> {
     accounts
 }
 
+/// Root of the legacy whitelist `get_proof` belongs to
+fn legacy_merkle_root() -> [u8; 32] {
+    let mut merkle_root = [0u8; 32];
+    hex::decode_to_slice(
+        "32f7f9803d8e88954435659db24d6fdaa94ba46165fa1ce076b03f232273b3a5",
+        &mut merkle_root,
+    )
+    .unwrap();
+    merkle_root
+}
+
 fn get_proof() -> Vec<(String, u8)> {
     // proof for seconde env user
     // left :0 , right:1
@@ -134,10 +153,7 @@ fn should_create_order_and_claim() {
     );
 
     // Set merkle root
-    ido_contract.set_merkle_root(
-        owner,
-        "32f7f9803d8e88954435659db24d6fdaa94ba46165fa1ce076b03f232273b3a5".to_string(),
-    );
+    ido_contract.set_merkle_root(owner, legacy_merkle_root(), Some(0));
 
     env.next_user();
     let user = env.next_user();
@@ -155,7 +171,9 @@ fn should_create_order_and_claim() {
             "ido_contract_hash" => ido_contract.contract_hash().to_formatted_string(),
             "tier" => tier,
             "proof" => get_proof(),
-            "amount" => amount
+            "amount" => amount,
+            "merkle_format" => Some(0u8),
+            "round_id" => Option::<String>::None
         },
         SystemTime::now()
             .checked_add(Duration::from_secs(20000))
@@ -164,6 +182,14 @@ fn should_create_order_and_claim() {
 
     let mut auction_schedules = ido_contract.schedules();
 
+    ido_contract.finalize(
+        owner,
+        SystemTime::now()
+            .checked_add(Duration::from_secs(7666660))
+            .unwrap(),
+    );
+    assert_eq!(ido_contract.status(), AuctionStatus::Finalized);
+
     ido_contract.claim(
         user,
         *auction_schedules.first_entry().unwrap().key(),
@@ -171,6 +197,14 @@ fn should_create_order_and_claim() {
             .checked_add(Duration::from_secs(7666660))
             .unwrap(),
     );
+    // Payment is escrowed in the contract purse until withdrawn
+    assert!(env.get_account(new_treasury_wallet).is_none());
+    ido_contract.withdraw_funds(
+        owner,
+        SystemTime::now()
+            .checked_add(Duration::from_secs(7666660))
+            .unwrap(),
+    );
     let treasury_wallet_balance = env.account_purse_balance(new_treasury_wallet);
     assert!(amount.eq(&treasury_wallet_balance));
     let _ = erc20.balance_of(Address::Account(user)).unwrap();
@@ -222,9 +256,13 @@ fn should_create_order_and_claim_erc20() {
         auction_end_time,
         auction_token_price,
         auction_token_capacity,
+        U256::zero(),
         pay_token_str,
         schedules,
         treasury_wallet,
+        deploy_factory(&env, owner)
+            .contract_hash()
+            .to_formatted_string(),
     );
     let ido_contract = casper_ido_instance;
 
@@ -247,10 +285,7 @@ fn should_create_order_and_claim_erc20() {
     );
 
     // Set merkle root
-    ido_contract.set_merkle_root(
-        owner,
-        "32f7f9803d8e88954435659db24d6fdaa94ba46165fa1ce076b03f232273b3a5".to_string(),
-    );
+    ido_contract.set_merkle_root(owner, legacy_merkle_root(), Some(0));
 
     let new_treasury_wallet = AccountHash::new([4u8; 32]);
     ido_contract.set_treasury_wallet(owner, new_treasury_wallet.to_formatted_string());
@@ -280,6 +315,14 @@ fn should_create_order_and_claim_erc20() {
 
     let mut auction_schedules = ido_contract.schedules();
 
+    ido_contract.finalize(
+        owner,
+        SystemTime::now()
+            .checked_add(Duration::from_secs(7666660))
+            .unwrap(),
+    );
+    assert_eq!(ido_contract.status(), AuctionStatus::Finalized);
+
     ido_contract.claim(
         user,
         *auction_schedules.first_entry().unwrap().key(),
@@ -287,6 +330,17 @@ fn should_create_order_and_claim_erc20() {
             .checked_add(Duration::from_secs(7666660))
             .unwrap(),
     );
+    let escrowed_balance = pay_token
+        .balance_of(Address::from(ido_contract.contract_package_hash()))
+        .unwrap();
+    assert!(amount.eq(&escrowed_balance));
+
+    ido_contract.withdraw_funds(
+        owner,
+        SystemTime::now()
+            .checked_add(Duration::from_secs(7666660))
+            .unwrap(),
+    );
     let treasury_wallet_balance = pay_token
         .balance_of(Address::from(new_treasury_wallet))
         .unwrap();
@@ -294,16 +348,1353 @@ fn should_create_order_and_claim_erc20() {
     let _ = erc20.balance_of(Address::Account(user)).unwrap();
 }
 
+/// Deploy a sale paid in an ERC20 token, with auction token and merkle root set
+fn deploy_with_pay_token(soft_cap: U256) -> (TestEnv, TestContext, ERC20Instance, AccountHash) {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let factory = deploy_factory(&env, owner);
+    let (test_context, pay_token) = deploy_sale(
+        &env,
+        owner,
+        soft_cap,
+        factory.contract_hash().to_formatted_string(),
+    );
+    (env, test_context, pay_token, owner)
+}
+
+/// Deploy the factory every sale registers to, with `owner` as admin
+fn deploy_factory(env: &TestEnv, owner: AccountHash) -> CasperFactoryInstance {
+    CasperFactoryInstance::new(
+        env,
+        "casper_factory",
+        owner,
+        U256::exp10(4),
+        AccountHash::new([7u8; 32]).to_formatted_string(),
+    )
+}
+
+fn deploy_sale(
+    env: &TestEnv,
+    owner: AccountHash,
+    soft_cap: U256,
+    factory_contract: String,
+) -> (TestContext, ERC20Instance) {
+    let since_the_epoch: u64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs();
+    let auction_token_capacity = U256::from(5000u32).checked_mul(U256::exp10(9)).unwrap();
+    let mut schedules: Schedules = Schedules::new();
+    schedules.insert(since_the_epoch + 666666, U256::from(4000));
+    schedules.insert(since_the_epoch + 777777, U256::from(6000));
+
+    let erc20_instance =
+        ERC20Instance::new(env, "Test_Token", owner, "ACME", 9, auction_token_capacity);
+    let pay_token = ERC20Instance::new(env, "USDT", owner, "USDT", 9, auction_token_capacity);
+    let casper_ido_instance = CasperIdoInstance::new(
+        env,
+        "casper_ido",
+        owner,
+        Time::from(since_the_epoch),
+        Time::from(since_the_epoch + 500000),
+        U256::from(2).checked_mul(U256::exp10(9)).unwrap(),
+        auction_token_capacity,
+        soft_cap,
+        Some(pay_token.contract_hash().to_formatted_string()),
+        schedules,
+        AccountHash::new([3u8; 32]).to_formatted_string(),
+        factory_contract,
+    );
+    let test_context = TestContext {
+        casper_ido_instance,
+        erc20_instance,
+    };
+
+    set_auction_token(&test_context, owner);
+    test_context
+        .casper_ido_instance
+        .set_merkle_root(owner, legacy_merkle_root(), Some(0));
+    (test_context, pay_token)
+}
+
+/// Fund the whitelisted user and let the ido contract spend it
+fn fund_whitelisted_user(
+    env: &TestEnv,
+    test_context: &TestContext,
+    pay_token: &ERC20Instance,
+    owner: AccountHash,
+    amount: U256,
+) -> AccountHash {
+    env.next_user();
+    let user = env.next_user();
+    fund_user(test_context, pay_token, owner, user, amount);
+    user
+}
+
+/// Send `amount` of pay token to `user` and let the ido contract spend it
+fn fund_user(
+    test_context: &TestContext,
+    pay_token: &ERC20Instance,
+    owner: AccountHash,
+    user: AccountHash,
+    amount: U256,
+) {
+    pay_token.transfer(owner, Address::from(user), amount);
+    pay_token.approve(
+        user,
+        Address::Contract(test_context.casper_ido_instance.contract_package_hash()),
+        amount,
+    );
+}
+
 #[test]
-fn should_add_orders() {
-    let (env, test_context, owner) = deploy();
+fn should_cancel_order_and_refund_erc20() {
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
     let ido_contract = test_context.casper_ido_instance;
-    let mut orders: BTreeMap<String, U256> = BTreeMap::new();
-    let ali = env.next_user();
-    let bob = env.next_user();
-    orders.insert(ali.to_formatted_string(), U256::one());
-    orders.insert(bob.to_formatted_string(), U256::one());
-    orders.insert(env.next_user().to_formatted_string(), U256::one());
-    orders.insert(env.next_user().to_formatted_string(), U256::one());
-    ido_contract.add_orders(owner, orders);
+
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    let sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(20000))
+        .unwrap();
+    ido_contract.create_order(user, tier, get_proof(), amount, sale_time);
+    assert_eq!(
+        pay_token.balance_of(Address::from(user)).unwrap(),
+        U256::zero()
+    );
+
+    ido_contract.cancel_order(user, sale_time);
+    assert_eq!(pay_token.balance_of(Address::from(user)).unwrap(), amount);
+}
+
+#[test]
+fn should_refund_when_soft_cap_not_reached() {
+    let soft_cap = U256::from(100u8).checked_mul(U256::exp10(9)).unwrap();
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(soft_cap);
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+    let erc20 = test_context.erc20_instance;
+
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    ido_contract.create_order(
+        user,
+        tier,
+        get_proof(),
+        amount,
+        SystemTime::now()
+            .checked_add(Duration::from_secs(20000))
+            .unwrap(),
+    );
+
+    let after_sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(600000))
+        .unwrap();
+    ido_contract.refund(user, after_sale_time);
+    assert_eq!(pay_token.balance_of(Address::from(user)).unwrap(), amount);
+
+    ido_contract.withdraw_auction_token(owner, after_sale_time);
+    assert_eq!(
+        erc20.balance_of(Address::from(owner)).unwrap(),
+        U256::from(5000u32).checked_mul(U256::exp10(9)).unwrap()
+    );
+}
+
+#[test]
+fn should_refund_cancelled_auction() {
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+    let erc20 = test_context.erc20_instance;
+
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    let sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(20000))
+        .unwrap();
+    ido_contract.create_order(user, tier, get_proof(), amount, sale_time);
+
+    ido_contract.cancel_auction(owner, sale_time);
+    assert_eq!(ido_contract.status(), AuctionStatus::Cancelled);
+    assert_eq!(
+        erc20.balance_of(Address::from(owner)).unwrap(),
+        U256::from(5000u32).checked_mul(U256::exp10(9)).unwrap()
+    );
+
+    ido_contract.refund(user, sale_time);
+    assert_eq!(pay_token.balance_of(Address::from(user)).unwrap(), amount);
+}
+
+#[test]
+fn should_claim_all_matured_schedules() {
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+    let erc20 = test_context.erc20_instance;
+
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    ido_contract.create_order(
+        user,
+        tier,
+        get_proof(),
+        amount,
+        SystemTime::now()
+            .checked_add(Duration::from_secs(20000))
+            .unwrap(),
+    );
+
+    let claim_time = SystemTime::now()
+        .checked_add(Duration::from_secs(7666660))
+        .unwrap();
+    ido_contract.finalize(owner, claim_time);
+    ido_contract.claim_all(user, claim_time);
+
+    // 50 USDT at 2 USDT each, both schedules in one transfer
+    assert_eq!(
+        erc20.balance_of(Address::from(user)).unwrap(),
+        U256::from(25u8).checked_mul(U256::exp10(9)).unwrap()
+    );
+}
+
+#[test]
+fn should_take_platform_fee_on_withdraw() {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let fee_wallet = AccountHash::new([7u8; 32]);
+    let factory = CasperFactoryInstance::new(
+        &env,
+        "casper_factory",
+        owner,
+        U256::exp10(4),
+        fee_wallet.to_formatted_string(),
+    );
+    let (test_context, pay_token) = deploy_sale(
+        &env,
+        owner,
+        U256::zero(),
+        factory.contract_hash().to_formatted_string(),
+    );
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+
+    // Only factory admins can set the fee, 5%
+    ido_contract.set_fee_numerator(user, U256::from(500), false);
+    ido_contract.set_fee_numerator(owner, U256::from(500), true);
+    // The sale keeps the denominator it was set with
+    factory.set_fee_denominator(owner, U256::exp10(2), true);
+
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    ido_contract.create_order(
+        user,
+        tier,
+        get_proof(),
+        amount,
+        SystemTime::now()
+            .checked_add(Duration::from_secs(20000))
+            .unwrap(),
+    );
+    let withdraw_time = SystemTime::now()
+        .checked_add(Duration::from_secs(600000))
+        .unwrap();
+    ido_contract.finalize(owner, withdraw_time);
+    ido_contract.withdraw_funds(owner, withdraw_time);
+
+    let fee_amount = U256::from(25u8).checked_mul(U256::exp10(8)).unwrap();
+    assert_eq!(
+        ido_contract.fee_amount(Some(pay_token.contract_hash())),
+        fee_amount
+    );
+    assert_eq!(
+        pay_token.balance_of(Address::from(fee_wallet)).unwrap(),
+        fee_amount
+    );
+    assert_eq!(
+        pay_token
+            .balance_of(Address::from(AccountHash::new([3u8; 32])))
+            .unwrap(),
+        amount.checked_sub(fee_amount).unwrap()
+    );
+}
+
+#[test]
+fn should_order_with_second_pay_token() {
+    let (env, test_context, _, owner) = deploy_with_pay_token(U256::zero());
+    let supply = U256::from(5000u32).checked_mul(U256::exp10(18)).unwrap();
+    let usdc = ERC20Instance::new(&env, "USDC", owner, "USDC", 18, supply);
+    let ido_contract = &test_context.casper_ido_instance;
+
+    // 2 USDC per auction token, whatever its decimals
+    let usdc_hash = Some(usdc.contract_hash().to_formatted_string());
+    ido_contract.add_pay_token(owner, usdc_hash.clone(), U256::from(2u8), Some(U256::one()));
+
+    let amount = U256::from(50u8).checked_mul(U256::exp10(18)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &usdc, owner, amount);
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    let sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(20000))
+        .unwrap();
+    ido_contract.create_order_with_pay_token(
+        user,
+        tier,
+        get_proof(),
+        amount,
+        usdc_hash.clone(),
+        sale_time,
+    );
+
+    // Orders are kept in main pay token units
+    let order_amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    assert_eq!(ido_contract.get_order(user, user, sale_time), order_amount);
+    assert_eq!(
+        ido_contract.raised_amount(user, usdc_hash, sale_time),
+        amount
+    );
+
+    let claim_time = SystemTime::now()
+        .checked_add(Duration::from_secs(800000))
+        .unwrap();
+    ido_contract.finalize(owner, claim_time);
+    assert_eq!(
+        ido_contract.claimable(user, user, claim_time),
+        U256::from(25u8).checked_mul(U256::exp10(9)).unwrap()
+    );
+
+    ido_contract.withdraw_funds(owner, claim_time);
+    assert_eq!(
+        usdc.balance_of(Address::from(AccountHash::new([3u8; 32])))
+            .unwrap(),
+        amount
+    );
+}
+
+#[test]
+fn should_claim_with_rational_price() {
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+
+    // 1/3 pay token per auction token
+    ido_contract.change_auction_token_price(owner, U256::one(), Some(U256::from(3u8)));
+
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    let sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(20000))
+        .unwrap();
+    ido_contract.create_order(user, tier, get_proof(), amount, sale_time);
+
+    let claim_time = SystemTime::now()
+        .checked_add(Duration::from_secs(800000))
+        .unwrap();
+    ido_contract.finalize(owner, claim_time);
+    assert_eq!(
+        ido_contract.claimable(user, user, claim_time),
+        U256::from(150u8).checked_mul(U256::exp10(9)).unwrap()
+    );
+
+    let auction_info = ido_contract.auction_info(user, claim_time);
+    assert_eq!(auction_info.auction_token_price, U256::one());
+    assert_eq!(
+        auction_info.auction_token_price_denominator,
+        U256::from(3u8)
+    );
+}
+
+#[test]
+fn should_pay_exact_entitlement_on_last_schedule() {
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+    let erc20 = test_context.erc20_instance;
+
+    // 3 pay tokens per auction token, 50 / 3 auction tokens do not divide evenly
+    ido_contract.change_auction_token_price(
+        owner,
+        U256::from(3u8).checked_mul(U256::exp10(9)).unwrap(),
+        None,
+    );
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    let sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(20000))
+        .unwrap();
+    ido_contract.create_order(user, tier, get_proof(), amount, sale_time);
+
+    let claim_time = SystemTime::now()
+        .checked_add(Duration::from_secs(800000))
+        .unwrap();
+    ido_contract.finalize(owner, claim_time);
+    let entitlement = U256::from(16666666666u64);
+    assert_eq!(
+        ido_contract.entitlement(user, user, claim_time),
+        entitlement
+    );
+
+    let schedule_times: Vec<u64> = ido_contract.schedules().keys().copied().collect();
+    ido_contract.claim(user, schedule_times[0], claim_time);
+    assert_eq!(
+        erc20.balance_of(Address::from(user)).unwrap(),
+        U256::from(6666666666u64)
+    );
+
+    // The last schedule pays the remainder instead of rounding down again
+    ido_contract.claim(user, schedule_times[1], claim_time);
+    assert_eq!(erc20.balance_of(Address::from(user)).unwrap(), entitlement);
+    assert_eq!(
+        ido_contract.claimed_amount(user, user, claim_time),
+        entitlement
+    );
+    assert_eq!(ido_contract.total_claimed(), entitlement);
+    assert_eq!(ido_contract.claimable(user, user, claim_time), U256::zero());
+}
+
+#[test]
+fn should_withdraw_unsold_and_sweep_unclaimed() {
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+    let erc20 = test_context.erc20_instance;
+
+    let since_the_epoch: u64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs();
+    let sweep_wallet = AccountHash::new([9u8; 32]);
+    ido_contract.set_claim_deadline(
+        owner,
+        since_the_epoch + 900000,
+        Some(sweep_wallet.to_formatted_string()),
+    );
+
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    let sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(20000))
+        .unwrap();
+    ido_contract.create_order(user, tier, get_proof(), amount, sale_time);
+
+    let claim_time = SystemTime::now()
+        .checked_add(Duration::from_secs(800000))
+        .unwrap();
+    ido_contract.finalize(owner, claim_time);
+    let schedule_times: Vec<u64> = ido_contract.schedules().keys().copied().collect();
+    ido_contract.claim(user, schedule_times[0], claim_time);
+
+    // 25 of 5000 auction tokens are sold
+    let creator_balance = erc20.balance_of(Address::from(owner)).unwrap();
+    ido_contract.withdraw_unsold(owner, claim_time);
+    assert_eq!(
+        erc20.balance_of(Address::from(owner)).unwrap(),
+        creator_balance
+            .checked_add(U256::from(4975u32).checked_mul(U256::exp10(9)).unwrap())
+            .unwrap()
+    );
+
+    // The second schedule is not claimed before the deadline
+    let sweep_time = SystemTime::now()
+        .checked_add(Duration::from_secs(1000000))
+        .unwrap();
+    ido_contract.claim_with_condition(user, schedule_times[1], sweep_time, false);
+    ido_contract.sweep_unclaimed(owner, sweep_time);
+    assert_eq!(
+        erc20.balance_of(Address::from(sweep_wallet)).unwrap(),
+        U256::from(15u8).checked_mul(U256::exp10(9)).unwrap()
+    );
+}
+
+#[test]
+fn should_create_order_with_sorted_pair_proof() {
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+
+    // Three leaves, the user is paired with the second one
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    let leaves = [
+        merkle_tree::leaf(user, tier),
+        merkle_tree::leaf(AccountHash::new([5u8; 32]), tier),
+        merkle_tree::leaf(AccountHash::new([6u8; 32]), tier),
+    ];
+    let merkle_root =
+        merkle_tree::hash_pair(&merkle_tree::hash_pair(&leaves[0], &leaves[1]), &leaves[2]);
+    ido_contract.set_merkle_root(owner, merkle_root, None);
+
+    let sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(20000))
+        .unwrap();
+    // A proof in the legacy shape is refused with a typed error
+    let error = ido_contract.create_order_with_error(user, tier, get_proof(), amount, sale_time);
+    assert_eq!(error, ApiError::from(Error::MalformedProof));
+
+    ido_contract.create_order(user, tier, vec![leaves[1], leaves[2]], amount, sale_time);
+    assert_eq!(ido_contract.get_order(user, user, sale_time), amount);
+}
+
+#[test]
+fn should_reject_invalid_proofs_with_typed_errors() {
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    let sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(20000))
+        .unwrap();
+
+    // Valid proof with another tier
+    let error =
+        ido_contract.create_order_with_error(user, U256::one(), get_proof(), amount, sale_time);
+    assert_eq!(error, ApiError::from(Error::NotWhiteListed));
+
+    let mut proof = get_proof();
+    proof[0].1 = 2;
+    let error = ido_contract.create_order_with_error(user, tier, proof, amount, sale_time);
+    assert_eq!(error, ApiError::from(Error::MalformedProof));
+
+    let mut proof = get_proof();
+    proof[0].0 = "not hex".to_string();
+    let error = ido_contract.create_order_with_error(user, tier, proof, amount, sale_time);
+    assert_eq!(error, ApiError::from(Error::MalformedProof));
+
+    let proof = vec![get_proof()[0].clone(); 33];
+    let error = ido_contract.create_order_with_error(user, tier, proof, amount, sale_time);
+    assert_eq!(error, ApiError::from(Error::ProofTooLong));
+}
+
+#[test]
+fn should_order_in_whitelist_rounds() {
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+
+    // Both rounds whitelist only the user, so the root is its leaf and the proof is empty
+    let tier = U256::from(20u8).checked_mul(U256::exp10(9)).unwrap();
+    let merkle_root = merkle_tree::leaf(user, tier);
+    let now: u64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    // Guaranteed round at 1 USDT instead of 2, then FCFS capped at 10 USDT
+    ido_contract.set_round(
+        owner,
+        "guaranteed",
+        merkle_root,
+        now,
+        now + 30000,
+        None,
+        Some(U256::exp10(9)),
+        None,
+    );
+    let max_order = U256::from(10u8).checked_mul(U256::exp10(9)).unwrap();
+    ido_contract.set_round(
+        owner,
+        "fcfs",
+        merkle_root,
+        now + 30000,
+        now + 500000,
+        Some(max_order),
+        None,
+        None,
+    );
+
+    let guaranteed_time = SystemTime::now()
+        .checked_add(Duration::from_secs(20000))
+        .unwrap();
+    let error = ido_contract.create_order_in_round_with_error(
+        user,
+        "fcfs",
+        tier,
+        vec![],
+        max_order,
+        guaranteed_time,
+    );
+    assert_eq!(error, ApiError::from(Error::OutOfRoundTime));
+
+    // 10 USDT at half price counts as 20 USDT of auction tokens at the auction price
+    ido_contract.create_order_in_round(
+        user,
+        "guaranteed",
+        tier,
+        vec![],
+        max_order,
+        guaranteed_time,
+    );
+    assert_eq!(ido_contract.get_order(user, user, guaranteed_time), tier);
+
+    let fcfs_time = SystemTime::now()
+        .checked_add(Duration::from_secs(40000))
+        .unwrap();
+    let error = ido_contract.create_order_in_round_with_error(
+        user,
+        "fcfs",
+        tier,
+        vec![],
+        max_order.checked_add(U256::one()).unwrap(),
+        fcfs_time,
+    );
+    assert_eq!(error, ApiError::from(Error::OutOfTier));
+    ido_contract.create_order_in_round(user, "fcfs", tier, vec![], max_order, fcfs_time);
+    assert_eq!(
+        ido_contract.round_order(user, user, "fcfs", fcfs_time),
+        max_order
+    );
+    assert_eq!(
+        ido_contract.get_order(user, user, fcfs_time),
+        tier.checked_add(max_order).unwrap()
+    );
+    assert_eq!(
+        pay_token.balance_of(Address::from(user)).unwrap(),
+        amount - max_order - max_order
+    );
+}
+
+#[test]
+fn should_accept_superseded_root_until_expiry_and_refuse_revoked_leaves() {
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+
+    // Single leaf roots, so every proof is empty
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    let other = env.next_user();
+    ido_contract.set_merkle_root(owner, merkle_tree::leaf(user, tier), None);
+    ido_contract.set_merkle_root_with_grace_period(owner, merkle_tree::leaf(other, tier), 30000);
+
+    let order_amount = U256::from(10u8).checked_mul(U256::exp10(9)).unwrap();
+    let grace_time = SystemTime::now()
+        .checked_add(Duration::from_secs(20000))
+        .unwrap();
+    ido_contract.create_order(user, tier, Vec::<[u8; 32]>::new(), order_amount, grace_time);
+    assert_eq!(ido_contract.get_order(user, user, grace_time), order_amount);
+
+    let expired_time = SystemTime::now()
+        .checked_add(Duration::from_secs(40000))
+        .unwrap();
+    let error = ido_contract.create_order_with_error(
+        user,
+        tier,
+        Vec::<[u8; 32]>::new(),
+        order_amount,
+        expired_time,
+    );
+    assert_eq!(error, ApiError::from(Error::NotWhiteListed));
+
+    ido_contract.revoke_leaves(owner, vec![merkle_tree::leaf(other, tier)], true);
+    let error = ido_contract.create_order_with_error(
+        other,
+        tier,
+        Vec::<[u8; 32]>::new(),
+        order_amount,
+        grace_time,
+    );
+    assert_eq!(error, ApiError::from(Error::LeafRevoked));
+}
+
+#[test]
+fn should_emit_events() {
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    let sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(20000))
+        .unwrap();
+    ido_contract.create_order(user, tier, get_proof(), amount, sale_time);
+    ido_contract.cancel_order(user, sale_time);
+
+    // auction_created, status_changed, merkle_root_changed, order_created, order_cancelled
+    assert_eq!(ido_contract.events_length(), 5);
+    let event_types: Vec<String> = (0..5)
+        .map(|index| ido_contract.event(index).unwrap()["event_type"].clone())
+        .collect();
+    assert_eq!(
+        event_types,
+        vec![
+            "auction_created",
+            "status_changed",
+            "merkle_root_changed",
+            "order_created",
+            "order_cancelled"
+        ]
+    );
+
+    let order_cancelled = ido_contract.event(4).unwrap();
+    assert_eq!(order_cancelled["account"], user.to_formatted_string());
+    assert_eq!(order_cancelled["amount"], amount.to_string());
+}
+
+#[test]
+fn should_query_order_and_claimable() {
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    let sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(20000))
+        .unwrap();
+    ido_contract.create_order(user, tier, get_proof(), amount, sale_time);
+    assert_eq!(ido_contract.get_order(user, user, sale_time), amount);
+    assert_eq!(ido_contract.get_order(user, owner, sale_time), U256::zero());
+    assert_eq!(ido_contract.sold_amount(user, sale_time), amount);
+    assert_eq!(ido_contract.claimable(user, user, sale_time), U256::zero());
+
+    let auction_info = ido_contract.auction_info(user, sale_time);
+    assert_eq!(auction_info.status, AuctionStatus::Live);
+    assert_eq!(auction_info.total_participants, 1);
+
+    // Only the first schedule (40%) is matured
+    let claim_time = SystemTime::now()
+        .checked_add(Duration::from_secs(700000))
+        .unwrap();
+    let first_schedule = *ido_contract.schedules().keys().next().unwrap();
+    ido_contract.finalize(owner, claim_time);
+    assert_eq!(
+        ido_contract.claimable(user, user, claim_time),
+        U256::from(10u8).checked_mul(U256::exp10(9)).unwrap()
+    );
+    assert!(!ido_contract.get_claimed(user, user, first_schedule, claim_time));
+
+    ido_contract.claim(user, first_schedule, claim_time);
+    assert!(ido_contract.get_claimed(user, user, first_schedule, claim_time));
+    assert_eq!(ido_contract.claimable(user, user, claim_time), U256::zero());
+}
+
+#[test]
+fn should_claim_vested_amount() {
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+    let erc20 = test_context.erc20_instance;
+
+    let since_the_epoch: u64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs();
+    // 20% at TGE, then linear over 100000 seconds after a 100000 seconds cliff
+    let tge_time = since_the_epoch + 600000;
+    ido_contract.set_vesting(owner, tge_time, U256::from(2000), 100000, 100000);
+
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    ido_contract.create_order(
+        user,
+        tier,
+        get_proof(),
+        amount,
+        SystemTime::now()
+            .checked_add(Duration::from_secs(20000))
+            .unwrap(),
+    );
+    ido_contract.finalize(
+        owner,
+        SystemTime::now()
+            .checked_add(Duration::from_secs(600000))
+            .unwrap(),
+    );
+
+    // 50 USDT at 2 USDT each
+    let total_amount = U256::from(25u8).checked_mul(U256::exp10(9)).unwrap();
+    ido_contract.claim(
+        user,
+        0,
+        UNIX_EPOCH
+            .checked_add(Duration::from_secs(tge_time + 1))
+            .unwrap(),
+    );
+    assert_eq!(
+        erc20.balance_of(Address::from(user)).unwrap(),
+        total_amount / 5
+    );
+
+    // Half of the linear part
+    ido_contract.claim(
+        user,
+        0,
+        UNIX_EPOCH
+            .checked_add(Duration::from_secs(tge_time + 150000))
+            .unwrap(),
+    );
+    assert_eq!(
+        erc20.balance_of(Address::from(user)).unwrap(),
+        total_amount * 3 / 5
+    );
+
+    ido_contract.claim(
+        user,
+        0,
+        UNIX_EPOCH
+            .checked_add(Duration::from_secs(tge_time + 200000))
+            .unwrap(),
+    );
+    assert_eq!(erc20.balance_of(Address::from(user)).unwrap(), total_amount);
+}
+
+#[test]
+fn should_claim_refund_of_oversubscribed_sale() {
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+
+    ido_contract.set_oversubscription(
+        owner,
+        true,
+        SystemTime::now()
+            .checked_sub(Duration::from_secs(50000))
+            .unwrap(),
+    );
+
+    // Whole capacity is already ordered, 5000 tokens at 2 USDT each
+    let capacity_in_pay_amount = U256::from(10000u32).checked_mul(U256::exp10(9)).unwrap();
+    let mut orders: BTreeMap<String, U256> = BTreeMap::new();
+    orders.insert(
+        env.next_user().to_formatted_string(),
+        capacity_in_pay_amount,
+    );
+    ido_contract.add_orders(owner, orders);
+
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    ido_contract.create_order(
+        user,
+        tier,
+        get_proof(),
+        amount,
+        SystemTime::now()
+            .checked_add(Duration::from_secs(20000))
+            .unwrap(),
+    );
+
+    let after_sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(600000))
+        .unwrap();
+    ido_contract.finalize(owner, after_sale_time);
+    ido_contract.claim_refund(user, after_sale_time);
+    // The unfilled part, rounded down
+    let refund_amount = amount
+        .checked_mul(amount)
+        .unwrap()
+        .checked_div(capacity_in_pay_amount.checked_add(amount).unwrap())
+        .unwrap();
+    assert_eq!(
+        pay_token.balance_of(Address::from(user)).unwrap(),
+        refund_amount
+    );
+}
+
+#[test]
+fn should_withdraw_filled_funds_after_refund_claims() {
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+
+    ido_contract.set_oversubscription(
+        owner,
+        true,
+        SystemTime::now()
+            .checked_sub(Duration::from_secs(50000))
+            .unwrap(),
+    );
+    let capacity_in_pay_amount = U256::from(10000u32).checked_mul(U256::exp10(9)).unwrap();
+    let mut orders: BTreeMap<String, U256> = BTreeMap::new();
+    orders.insert(
+        env.next_user().to_formatted_string(),
+        capacity_in_pay_amount,
+    );
+    ido_contract.add_orders(owner, orders);
+
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    ido_contract.create_order(
+        user,
+        tier,
+        get_proof(),
+        amount,
+        SystemTime::now()
+            .checked_add(Duration::from_secs(20000))
+            .unwrap(),
+    );
+
+    // Refund is claimed before the treasury withdraws
+    let after_sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(600000))
+        .unwrap();
+    ido_contract.finalize(owner, after_sale_time);
+    ido_contract.claim_refund(user, after_sale_time);
+    ido_contract.withdraw_funds(owner, after_sale_time);
+
+    let refund_amount = amount
+        .checked_mul(amount)
+        .unwrap()
+        .checked_div(capacity_in_pay_amount.checked_add(amount).unwrap())
+        .unwrap();
+    assert_eq!(
+        pay_token
+            .balance_of(Address::from(AccountHash::new([3u8; 32])))
+            .unwrap(),
+        amount.checked_sub(refund_amount).unwrap()
+    );
+}
+
+#[test]
+fn should_empty_escrow_after_every_refund_and_withdraw() {
+    let (env, test_context, usdt, owner) = deploy_with_pay_token(U256::zero());
+    let supply = U256::from(5000u32).checked_mul(U256::exp10(18)).unwrap();
+    let usdc = ERC20Instance::new(&env, "USDC", owner, "USDC", 18, supply);
+    let ido_contract = &test_context.casper_ido_instance;
+    let usdc_hash = Some(usdc.contract_hash().to_formatted_string());
+    ido_contract.add_pay_token(owner, usdc_hash.clone(), U256::from(2u8), Some(U256::one()));
+    ido_contract.set_oversubscription(
+        owner,
+        true,
+        SystemTime::now()
+            .checked_sub(Duration::from_secs(50000))
+            .unwrap(),
+    );
+
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    let users = [
+        env.next_user(),
+        env.next_user(),
+        env.next_user(),
+        env.next_user(),
+    ];
+    let (merkle_root, proof, proof_flags) = four_leaf_multiproof(users, tier);
+    ido_contract.set_merkle_root(owner, merkle_root, None);
+    ido_contract.register_whitelist(
+        owner,
+        users
+            .iter()
+            .map(|user| (user.to_formatted_string(), tier))
+            .collect(),
+        proof,
+        proof_flags,
+    );
+
+    // 15000 USDT worth is ordered for a capacity of 10000, a third of every order is refunded
+    // and that third doesn't divide evenly
+    let sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(20000))
+        .unwrap();
+    let usdt_amount = U256::from(4000u32).checked_mul(U256::exp10(9)).unwrap();
+    for user in &users[..3] {
+        fund_user(&test_context, &usdt, owner, *user, usdt_amount);
+        ido_contract.create_order(*user, tier, Vec::<[u8; 32]>::new(), usdt_amount, sale_time);
+    }
+    let usdc_amount = U256::from(3000u32).checked_mul(U256::exp10(18)).unwrap();
+    fund_user(&test_context, &usdc, owner, users[3], usdc_amount);
+    ido_contract.create_order_with_pay_token(
+        users[3],
+        tier,
+        Vec::<[u8; 32]>::new(),
+        usdc_amount,
+        usdc_hash,
+        sale_time,
+    );
+
+    let after_sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(600000))
+        .unwrap();
+    ido_contract.finalize(owner, after_sale_time);
+    for user in users {
+        ido_contract.claim_refund(user, after_sale_time);
+    }
+    ido_contract.withdraw_funds(owner, after_sale_time);
+
+    let escrow = Address::from(ido_contract.contract_package_hash());
+    assert_eq!(usdt.balance_of(escrow).unwrap(), U256::zero());
+    assert_eq!(usdc.balance_of(escrow).unwrap(), U256::zero());
+    assert_eq!(
+        usdt.balance_of(Address::from(users[0])).unwrap(),
+        U256::from(1333333333333u64)
+    );
+}
+
+#[test]
+fn should_validate_time_schedules() {
+    let (_, test_context, owner) = deploy();
+    let ido_contract = test_context.casper_ido_instance;
+
+    let since_the_epoch: u64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs();
+    let auction_start_time = Time::from(since_the_epoch);
+    let auction_end_time = Time::from(since_the_epoch + 500000);
+
+    // Percents don't add up to 10000
+    let mut schedules: Schedules = Schedules::new();
+    schedules.insert(since_the_epoch + 666666, U256::from(4000));
+    ido_contract.change_time_schedules(
+        owner,
+        auction_start_time,
+        auction_end_time,
+        schedules.clone(),
+        false,
+    );
+
+    // Schedule unlocks before the sale ends
+    schedules.insert(since_the_epoch + 400000, U256::from(6000));
+    ido_contract.change_time_schedules(
+        owner,
+        auction_start_time,
+        auction_end_time,
+        schedules.clone(),
+        false,
+    );
+
+    // Sale ends before it starts
+    schedules.remove(&(since_the_epoch + 400000));
+    schedules.insert(since_the_epoch + 777777, U256::from(6000));
+    ido_contract.change_time_schedules(
+        owner,
+        auction_end_time,
+        auction_start_time,
+        schedules.clone(),
+        false,
+    );
+
+    ido_contract.change_time_schedules(
+        owner,
+        auction_start_time,
+        auction_end_time,
+        schedules,
+        true,
+    );
+}
+
+#[test]
+fn should_freeze_schedules_after_zero_amount_claim() {
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+
+    let since_the_epoch: u64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs();
+    let auction_start_time = Time::from(since_the_epoch);
+    let auction_end_time = Time::from(since_the_epoch + 500000);
+    // The first schedule unlocks nothing
+    let mut schedules: Schedules = Schedules::new();
+    schedules.insert(since_the_epoch + 600000, U256::zero());
+    schedules.insert(since_the_epoch + 666666, U256::from(4000));
+    schedules.insert(since_the_epoch + 777777, U256::from(6000));
+    ido_contract.change_time_schedules(
+        owner,
+        auction_start_time,
+        auction_end_time,
+        schedules.clone(),
+        true,
+    );
+
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    let sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(20000))
+        .unwrap();
+    ido_contract.create_order(user, tier, get_proof(), amount, sale_time);
+
+    let claim_time = SystemTime::now()
+        .checked_add(Duration::from_secs(650000))
+        .unwrap();
+    ido_contract.finalize(owner, claim_time);
+    // Schedules can still change before anyone claims
+    ido_contract.change_time_schedules(
+        owner,
+        auction_start_time,
+        auction_end_time,
+        schedules.clone(),
+        true,
+    );
+
+    ido_contract.claim(user, since_the_epoch + 600000, claim_time);
+    assert!(ido_contract.get_claimed(user, user, since_the_epoch + 600000, claim_time));
+    assert_eq!(ido_contract.total_claimed(), U256::zero());
+    ido_contract.change_time_schedules(
+        owner,
+        auction_start_time,
+        auction_end_time,
+        schedules,
+        false,
+    );
+}
+
+fn set_auction_token(test_context: &TestContext, owner: AccountHash) {
+    let ido_contract = &test_context.casper_ido_instance;
+    let erc20 = &test_context.erc20_instance;
+    let auction_token_capacity = U256::from(5000u32).checked_mul(U256::exp10(9)).unwrap();
+    erc20.approve(
+        owner,
+        Address::from(ido_contract.contract_package_hash()),
+        auction_token_capacity,
+    );
+    ido_contract.set_auction_token(
+        owner,
+        erc20.contract_hash().to_formatted_string(),
+        auction_token_capacity,
+        SystemTime::now()
+            .checked_sub(Duration::from_secs(50000))
+            .unwrap(),
+    );
+}
+
+#[test]
+fn should_add_orders() {
+    let (env, test_context, owner) = deploy();
+    set_auction_token(&test_context, owner);
+    let ido_contract = test_context.casper_ido_instance;
+    let mut orders: BTreeMap<String, U256> = BTreeMap::new();
+    let ali = env.next_user();
+    let bob = env.next_user();
+    orders.insert(ali.to_formatted_string(), U256::one());
+    orders.insert(bob.to_formatted_string(), U256::one());
+    orders.insert(env.next_user().to_formatted_string(), U256::one());
+    orders.insert(env.next_user().to_formatted_string(), U256::one());
+    ido_contract.add_orders(owner, orders);
+}
+
+#[test]
+fn should_not_add_orders_over_capacity() {
+    let (env, test_context, owner) = deploy();
+    set_auction_token(&test_context, owner);
+    let ido_contract = test_context.casper_ido_instance;
+
+    // 5000 tokens at 2 CSPR each
+    let capacity_in_pay_amount = U256::from(10000u32).checked_mul(U256::exp10(9)).unwrap();
+    let mut orders: BTreeMap<String, U256> = BTreeMap::new();
+    orders.insert(
+        env.next_user().to_formatted_string(),
+        capacity_in_pay_amount,
+    );
+    orders.insert(env.next_user().to_formatted_string(), U256::one());
+    ido_contract.add_orders_with_condition(owner, orders, false);
+}
+
+/// Args of the `pre_create_order` session ordering `amount` CSPR with the legacy proof
+fn pre_create_order_args(ido_contract: &CasperIdoInstance, amount: U512) -> RuntimeArgs {
+    runtime_args! {
+        "ido_contract_hash" => ido_contract.contract_hash().to_formatted_string(),
+        "tier" => U256::from(2u8).checked_mul(U256::exp10(18)).unwrap(),
+        "proof" => get_proof(),
+        "amount" => amount,
+        "merkle_format" => Some(0u8),
+        "round_id" => Option::<String>::None
+    }
+}
+
+/// Order 9000 of the 10000 pay amount capacity for another account
+fn fill_capacity_but_1000(env: &TestEnv, ido_contract: &CasperIdoInstance, owner: AccountHash) {
+    let mut orders: BTreeMap<String, U256> = BTreeMap::new();
+    orders.insert(
+        env.next_user().to_formatted_string(),
+        U256::from(9000u32).checked_mul(U256::exp10(9)).unwrap(),
+    );
+    ido_contract.add_orders(owner, orders);
+}
+
+#[test]
+fn should_return_cspr_excess_of_partially_filled_order() {
+    let (env, test_context, owner) = deploy();
+    set_auction_token(&test_context, owner);
+    let ido_contract = test_context.casper_ido_instance;
+    ido_contract.set_merkle_root(owner, legacy_merkle_root(), Some(0));
+    ido_contract.set_partial_fill(
+        owner,
+        true,
+        SystemTime::now()
+            .checked_sub(Duration::from_secs(50000))
+            .unwrap(),
+    );
+    env.next_user();
+    let user = env.next_user();
+    fill_capacity_but_1000(&env, &ido_contract, owner);
+
+    let amount = U512::from(5000u32).checked_mul(U512::exp10(9)).unwrap();
+    let sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(20000))
+        .unwrap();
+    let balance = env.account_purse_balance(user);
+    env.run_with_time(
+        user,
+        DeploySource::Code(PathBuf::from(PRE_CREATE_ORDER_WASM)),
+        pre_create_order_args(&ido_contract, amount),
+        sale_time,
+    );
+
+    let filled_amount = U256::from(1000u32).checked_mul(U256::exp10(9)).unwrap();
+    assert_eq!(ido_contract.get_order(user, user, sale_time), filled_amount);
+    assert_eq!(
+        ido_contract.raised_amount(user, None, sale_time),
+        filled_amount
+    );
+    // Only the filled 1000 CSPR and the deploy payment leave the account
+    let spent = balance
+        .checked_sub(env.account_purse_balance(user))
+        .unwrap();
+    assert!(spent > U512::from(1000u32).checked_mul(U512::exp10(9)).unwrap());
+    assert!(spent < amount);
+}
+
+#[test]
+fn should_pull_only_filled_part_of_erc20_order() {
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(5000u32).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+    ido_contract.set_partial_fill(
+        owner,
+        true,
+        SystemTime::now()
+            .checked_sub(Duration::from_secs(50000))
+            .unwrap(),
+    );
+    fill_capacity_but_1000(&env, &ido_contract, owner);
+
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    let sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(20000))
+        .unwrap();
+    ido_contract.create_order(user, tier, get_proof(), amount, sale_time);
+
+    let filled_amount = U256::from(1000u32).checked_mul(U256::exp10(9)).unwrap();
+    assert_eq!(ido_contract.get_order(user, user, sale_time), filled_amount);
+    assert_eq!(
+        pay_token.balance_of(Address::from(user)).unwrap(),
+        amount.checked_sub(filled_amount).unwrap()
+    );
+    assert_eq!(
+        pay_token
+            .balance_of(Address::from(ido_contract.contract_package_hash()))
+            .unwrap(),
+        filled_amount
+    );
+}
+
+#[test]
+fn should_refuse_orders_over_capacity_without_partial_fill() {
+    let sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(20000))
+        .unwrap();
+
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(5000u32).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+    fill_capacity_but_1000(&env, &ido_contract, owner);
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    let error = ido_contract.create_order_with_error(user, tier, get_proof(), amount, sale_time);
+    assert_eq!(error, ApiError::from(Error::OutOfCapacity));
+
+    let (env, test_context, owner) = deploy();
+    set_auction_token(&test_context, owner);
+    let ido_contract = test_context.casper_ido_instance;
+    ido_contract.set_merkle_root(owner, legacy_merkle_root(), Some(0));
+    env.next_user();
+    let user = env.next_user();
+    fill_capacity_but_1000(&env, &ido_contract, owner);
+    let error = env.run_with_time_and_error(
+        user,
+        DeploySource::Code(PathBuf::from(PRE_CREATE_ORDER_WASM)),
+        pre_create_order_args(
+            &ido_contract,
+            U512::from(5000u32).checked_mul(U512::exp10(9)).unwrap(),
+        ),
+        sale_time,
+    );
+    assert_eq!(error, ApiError::from(Error::OutOfCapacity));
+}
+
+/// Sorted pair tree of four leaves and the multiproof of the first and third ones
+fn four_leaf_multiproof(
+    accounts: [AccountHash; 4],
+    tier: U256,
+) -> ([u8; 32], Vec<[u8; 32]>, Vec<bool>) {
+    let leaves = accounts.map(|account| merkle_tree::leaf(account, tier));
+    let merkle_root = merkle_tree::hash_pair(
+        &merkle_tree::hash_pair(&leaves[0], &leaves[1]),
+        &merkle_tree::hash_pair(&leaves[2], &leaves[3]),
+    );
+    (
+        merkle_root,
+        vec![leaves[1], leaves[3]],
+        vec![false, false, true],
+    )
+}
+
+#[test]
+fn should_add_verified_orders() {
+    let (env, test_context, owner) = deploy();
+    set_auction_token(&test_context, owner);
+    let ido_contract = test_context.casper_ido_instance;
+
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    let ali = env.next_user();
+    let bob = env.next_user();
+    let (merkle_root, proof, proof_flags) = four_leaf_multiproof(
+        [
+            ali,
+            AccountHash::new([5u8; 32]),
+            bob,
+            AccountHash::new([7u8; 32]),
+        ],
+        tier,
+    );
+    ido_contract.set_merkle_root(owner, merkle_root, None);
+
+    let orders = vec![
+        (ali.to_formatted_string(), tier, U256::one()),
+        (bob.to_formatted_string(), tier, U256::from(2u8)),
+    ];
+    ido_contract.add_verified_orders(owner, orders, proof, proof_flags);
+    let now = SystemTime::now();
+    assert_eq!(ido_contract.get_order(owner, ali, now), U256::one());
+    assert_eq!(ido_contract.get_order(owner, bob, now), U256::from(2u8));
+}
+
+#[test]
+fn should_order_after_whitelist_registration() {
+    let (env, test_context, pay_token, owner) = deploy_with_pay_token(U256::zero());
+    let amount = U256::from(50u8).checked_mul(U256::exp10(9)).unwrap();
+    let user = fund_whitelisted_user(&env, &test_context, &pay_token, owner, amount);
+    let ido_contract = test_context.casper_ido_instance;
+
+    let tier = U256::from(2u8).checked_mul(U256::exp10(18)).unwrap();
+    let other = env.next_user();
+    let (merkle_root, proof, proof_flags) = four_leaf_multiproof(
+        [
+            user,
+            AccountHash::new([5u8; 32]),
+            other,
+            AccountHash::new([7u8; 32]),
+        ],
+        tier,
+    );
+    ido_contract.set_merkle_root(owner, merkle_root, None);
+    ido_contract.register_whitelist(
+        owner,
+        vec![
+            (user.to_formatted_string(), tier),
+            (other.to_formatted_string(), tier),
+        ],
+        proof,
+        proof_flags,
+    );
+
+    // Registered accounts order without a proof
+    let sale_time = SystemTime::now()
+        .checked_add(Duration::from_secs(20000))
+        .unwrap();
+    ido_contract.create_order(user, tier, Vec::<[u8; 32]>::new(), amount, sale_time);
+    assert_eq!(ido_contract.get_order(user, user, sale_time), amount);
 }
@@ -2,6 +2,9 @@
 
 extern crate alloc;
 
+#[cfg(test)]
+pub mod casper_factory_instance;
+
 #[cfg(test)]
 pub mod casper_ido_instance;
 
@@ -10,3 +13,6 @@ pub mod erc20_instance;
 
 #[cfg(test)]
 pub mod casper_ido_test;
+
+#[cfg(test)]
+pub mod casper_factory_test;
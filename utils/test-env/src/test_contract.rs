@@ -1,8 +1,8 @@
-use std::time::SystemTime;
+use std::{path::PathBuf, time::SystemTime};
 
 use casper_types::{
-    account::AccountHash, bytesrepr::FromBytes, CLTyped, ContractHash, ContractPackageHash,
-    RuntimeArgs,
+    account::AccountHash, bytesrepr::FromBytes, ApiError, CLTyped, ContractHash,
+    ContractPackageHash, RuntimeArgs,
 };
 
 use crate::{utils::DeploySource, TestEnv};
@@ -109,4 +109,54 @@ impl TestContract {
         self.env
             .run_with_time(sender, session_code, session_args, time);
     }
+
+    pub fn call_contract_with_time_and_condition(
+        &self,
+        sender: AccountHash,
+        entry_point: &str,
+        session_args: RuntimeArgs,
+        time: SystemTime,
+        success: bool,
+    ) {
+        let session_code = DeploySource::ByHash {
+            hash: self.contract_hash(),
+            method: entry_point.to_string(),
+        };
+        self.env
+            .run_with_time_and_condition(sender, session_code, session_args, time, success);
+    }
+
+    /// Call an entry point which must revert and return its error
+    pub fn call_contract_with_time_and_error(
+        &self,
+        sender: AccountHash,
+        entry_point: &str,
+        session_args: RuntimeArgs,
+        time: SystemTime,
+    ) -> ApiError {
+        let session_code = DeploySource::ByHash {
+            hash: self.contract_hash(),
+            method: entry_point.to_string(),
+        };
+        self.env
+            .run_with_time_and_error(sender, session_code, session_args, time)
+    }
+
+    /// Run `wasm` session code which stores a value under the sender's `result` named key
+    pub fn query_with_session<T: CLTyped + FromBytes>(
+        &self,
+        sender: AccountHash,
+        wasm: &str,
+        session_args: RuntimeArgs,
+        time: SystemTime,
+    ) -> T {
+        self.env.run_with_time(
+            sender,
+            DeploySource::Code(PathBuf::from(wasm)),
+            session_args,
+            time,
+        );
+        self.env
+            .query_account_named_key(sender, &["result".to_string()])
+    }
 }
@@ -7,11 +7,13 @@ use casper_engine_test_support::{
     ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_RUN_GENESIS_REQUEST,
 };
 use casper_types::{
-    account::AccountHash, bytesrepr::FromBytes, CLTyped, Key, PublicKey, RuntimeArgs, SecretKey,
-    U512,
+    account::AccountHash, bytesrepr::FromBytes, ApiError, CLTyped, Key, PublicKey, RuntimeArgs,
+    SecretKey, U512,
 };
 
-use crate::utils::{deploy, fund_account, query, query_dictionary_item, DeploySource};
+use crate::utils::{
+    deploy, deploy_with_error, fund_account, query, query_dictionary_item, DeploySource,
+};
 
 #[derive(Clone)]
 pub struct TestEnv {
@@ -95,6 +97,49 @@ impl TestEnv {
         )
     }
 
+    pub fn run_with_time_and_condition(
+        &self,
+        sender: AccountHash,
+        session_code: DeploySource,
+        session_args: RuntimeArgs,
+        time: SystemTime,
+        success: bool,
+    ) {
+        let since_the_epoch: u64 = time
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs();
+        deploy(
+            &mut self.state.lock().unwrap().builder,
+            &sender,
+            &session_code,
+            session_args,
+            success,
+            Some(since_the_epoch),
+        )
+    }
+
+    /// Run a deploy which must revert and return its error
+    pub fn run_with_time_and_error(
+        &self,
+        sender: AccountHash,
+        session_code: DeploySource,
+        session_args: RuntimeArgs,
+        time: SystemTime,
+    ) -> ApiError {
+        let since_the_epoch: u64 = time
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs();
+        deploy_with_error(
+            &mut self.state.lock().unwrap().builder,
+            &sender,
+            &session_code,
+            session_args,
+            Some(since_the_epoch),
+        )
+    }
+
     pub fn get_account(&self, account_hash: AccountHash) -> Option<casper_types::account::Account> {
         self.state.lock().unwrap().builder.get_account(account_hash)
     }
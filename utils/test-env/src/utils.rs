@@ -4,11 +4,14 @@ use casper_engine_test_support::{
     DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder, ARG_AMOUNT,
     DEFAULT_ACCOUNT_ADDR, DEFAULT_PAYMENT,
 };
-use casper_execution_engine::core::engine_state::execute_request::ExecuteRequest;
+use casper_execution_engine::core::{
+    engine_state::{self, execute_request::ExecuteRequest},
+    execution,
+};
 
 use casper_types::{
-    account::AccountHash, bytesrepr::FromBytes, runtime_args, system::mint, CLTyped, ContractHash,
-    Key, RuntimeArgs, StoredValue, U512,
+    account::AccountHash, bytesrepr::FromBytes, runtime_args, system::mint, ApiError, CLTyped,
+    ContractHash, Key, RuntimeArgs, StoredValue, U512,
 };
 
 pub fn query<T: FromBytes + CLTyped>(
@@ -81,6 +84,21 @@ pub fn deploy(
     .commit();
 }
 
+/// Run a deploy which must revert and return its error
+pub fn deploy_with_error(
+    builder: &mut InMemoryWasmTestBuilder,
+    deployer: &AccountHash,
+    source: &DeploySource,
+    args: RuntimeArgs,
+    block_time: Option<u64>,
+) -> ApiError {
+    deploy(builder, deployer, source, args, false, block_time);
+    match builder.get_error() {
+        Some(engine_state::Error::Exec(execution::Error::Revert(api_error))) => api_error,
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
+
 pub fn query_dictionary_item(
     builder: &InMemoryWasmTestBuilder,
     key: Key,